@@ -21,6 +21,9 @@ struct Vertex {
     @location(5) instance_color_primary: vec4<f32>,
     @location(6) instance_color_secondary: vec4<f32>,
     @location(7) instance_phase: f32,
+    @location(8) instance_halo: f32,
+    @location(9) instance_decay: f32,
+    @location(10) instance_carried: f32,
 };
 
 struct VertexOutput {
@@ -39,6 +42,9 @@ struct VertexOutput {
     @location(5) instance_color_primary: vec4<f32>,
     @location(6) instance_color_secondary: vec4<f32>,
     @location(7) instance_phase: f32,
+    @location(8) instance_halo: f32,
+    @location(9) instance_decay: f32,
+    @location(10) instance_carried: f32,
 }
 
 @vertex
@@ -75,6 +81,9 @@ fn vertex(vertex: Vertex) -> VertexOutput {
     out.instance_color_primary = vertex.instance_color_primary;
     out.instance_color_secondary = vertex.instance_color_secondary;
     out.instance_phase = vertex.instance_phase;
+    out.instance_halo = vertex.instance_halo;
+    out.instance_decay = vertex.instance_decay;
+    out.instance_carried = vertex.instance_carried;
     return out;
 }
 
@@ -131,7 +140,9 @@ fn fragment_side(mesh: VertexOutput) -> vec4<f32> {
     d_s = opUnion(d_s, sd_segment(pos, vec2(30., -40.), vec2(60. + anim_1 * 10., 65.)));
     d_s = opUnion(d_s, sd_segment(pos, vec2(30., -40.), vec2(30., 20.)));
 
-    return combine_ant_parts(mesh, d_p, d_s);
+    let color = combine_ant_parts(mesh, d_p, d_s);
+    // In front of the head
+    return draw_carried_item(mesh, pos + vec2(110., -20.), color);
 }
 
 fn fragment_top(mesh: VertexOutput) -> vec4<f32> {
@@ -160,8 +171,9 @@ fn fragment_top(mesh: VertexOutput) -> vec4<f32> {
     d_s = opUnion(d_s, sd_segment(pos, vec2(40., 30.), vec2(75., 80. + anim_1 * 10.)));
     d_s = opUnion(d_s, sd_segment(pos, vec2(40., 30.), vec2(20., 30.)));
 
-
-    return combine_ant_parts(mesh, d_p, d_s);
+    let color = combine_ant_parts(mesh, d_p, d_s);
+    // In front of the head
+    return draw_carried_item(mesh, pos + vec2(0., 110.), color);
 }
 
 fn fragment_dead(mesh: VertexOutput) -> vec4<f32> {
@@ -192,10 +204,12 @@ fn fragment_dead(mesh: VertexOutput) -> vec4<f32> {
     d_s = opUnion(d_s, sd_segment(pos, vec2(30., -40.), vec2(30., 20.)));
 
     var color = combine_ant_parts(mesh, d_p, d_s);
-    let factor = 0.8;
+    // Darken then fade away while decomposing
+    let factor = 0.8 * (1. - 0.5 * mesh.instance_decay);
     color.r = color.r * factor;
     color.g = color.g * factor;
     color.b = color.b * factor;
+    color.a = color.a * (1. - mesh.instance_decay);
     return color;
 }
 
@@ -206,17 +220,41 @@ fn combine_ant_parts(mesh: VertexOutput, d_p: f32, d_s: f32) -> vec4<f32> {
     let color = select(secondary_color.rgb, primary_color.rgb, secondary_color.a < primary_color.a);
     // let color = blend_colors(primary_color, secondary_color);
 
-
-    // let color_halo = vec3(1., 0.6, 0.);
-    // var halo = sd_color_halo(opUnion(d_s, d_p), 50., color_halo);
-    // let halo_blink: f32 = cos(globals.time * 2. * PI / 2.) * 0.5 + 0.5;
-    // halo.a = halo.a * halo_blink;
-
-    // return blend_colors(halo, vec4(color.r, color.g, color.b, alpha));
+    if mesh.instance_halo > 0. {
+        let color_halo = vec3(1., 0.1, 0.);
+        var halo = sd_color_halo(opUnion(d_s, d_p), 50., color_halo);
+        let halo_blink: f32 = cos(globals.time * 2. * PI / 2.) * 0.5 + 0.5;
+        halo.a = halo.a * halo_blink * mesh.instance_halo;
+        return blend_colors(halo, vec4(color.r, color.g, color.b, alpha));
+    }
     return vec4(color.r, color.g, color.b, alpha);
     // return color;
 }
 
+// Must match CarriedItem
+const CARRIED_FOOD: u32 = 1u;
+const CARRIED_STOLEN_FOOD: u32 = 2u;
+const CARRIED_CORPSE: u32 = 3u;
+
+fn draw_carried_item(mesh: VertexOutput, pos: vec2<f32>, color: vec4<f32>) -> vec4<f32> {
+    let item = u32(mesh.instance_carried + 0.5);
+    var d: f32;
+    var item_color: vec3<f32>;
+    if item == CARRIED_FOOD {
+        d = sd_circle(pos, 22.);
+        item_color = vec3(0.95, 0.85, 0.5);
+    } else if item == CARRIED_STOLEN_FOOD {
+        // Thieves grab more than they can carry properly
+        d = sd_circle(pos + vec2(0., -10.), 32.);
+        item_color = vec3(0.5, 0.8, 0.1);
+    } else if item == CARRIED_CORPSE {
+        d = sd_rounded_box(rotate(pos, PI / 4.), vec2(50., 18.), 18.);
+        item_color = vec3(0.15, 0.12, 0.1);
+    } else {
+        return color;
+    }
+    return blend_colors(color, sd_color_smooth(d - 3., item_color));
+}
 
 fn sd_color_halo(d: f32, width: f32, color: vec3<f32>) -> vec4<f32> {
     let x = pow(d / width, 0.3);
@@ -225,7 +263,7 @@ fn sd_color_halo(d: f32, width: f32, color: vec3<f32>) -> vec4<f32> {
 }
 
 fn blend_colors(dest: vec4<f32>, source: vec4<f32>) -> vec4<f32> {
-    return source.a * source.rbga + (1. - source.a) * dest.rgba;
+    return source.a * source.rgba + (1. - source.a) * dest.rgba;
 }
 
 fn sd_color_sharp(d: f32, color: vec3<f32>) -> vec4<f32> {
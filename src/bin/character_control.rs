@@ -9,11 +9,8 @@ use bevy_inspector_egui::quick::WorldInspectorPlugin;
 use bevy_rapier2d::prelude::*;
 use last_of_ants::{
     components::{
-        ants::{
-            goal::AntGoal, job::Job, live_ants::LiveAntBundle, movement::position::debug_ants,
-            zombants::ZombAntBundle, AntColorKind,
-        },
-        nav_mesh::{debug_nav_mesh, NavNode},
+        ants::movement::position::debug_ants,
+        nav_mesh::debug_nav_mesh,
         player::{update_player_sensor, Player},
     },
     helpers::{on_key_just_pressed, toggle_on_key, toggle_physics_debug},
@@ -26,7 +23,6 @@ use last_of_ants::{
     ui::ui_clues::UiCluesPlugin,
     AppState, GamePlugin, COLLISION_GROUP_ANTS, COLLISION_GROUP_EXPLOSION, PLAYER_SIZE, TILE_SIZE,
 };
-use rand::{seq::IteratorRandom, Rng};
 
 fn main() {
     App::new()
@@ -51,10 +47,7 @@ fn main() {
                 spawn_explosions,
             ),
         )
-        .add_systems(
-            OnEnter(AppState::ProcessingOthers),
-            (spawn_ants_on_navmesh, attach_camera_to_player),
-        )
+        .add_systems(OnEnter(AppState::ProcessingOthers), attach_camera_to_player)
         .insert_resource(LevelSelection::index(0))
         .run();
 }
@@ -250,104 +243,6 @@ fn attach_camera_to_player(
     }
 }
 
-fn spawn_ants_on_navmesh(
-    mut commands: Commands,
-    // mut level_events: EventReader<LevelEvent>,
-    nav_nodes: Query<(Entity, &GlobalTransform, &NavNode)>,
-    level: Query<(&LevelIid, &Children)>,
-    named_transform: Query<(Entity, &Name, &GlobalTransform)>,
-) {
-    // for level_event in level_events.read() {
-    //     let LevelEvent::Transformed(level_iid) = level_event else {
-    //         continue;
-    //     };
-    let mut rng = rand::thread_rng();
-    // let level_children = level.iter().find(|(iid, _)| *iid == level_iid).unwrap().1;
-    let level_children = level.single().1;
-    let (entities_holder, _, entities_holder_pos) = level_children
-        .iter()
-        .filter_map(|child| named_transform.get(*child).ok())
-        .find(|(_, name, _)| name.as_str() == "Entities")
-        .unwrap();
-
-    for _ in 0..100 {
-        let Some((nav_node_entity, nav_node_pos, nav_node)) = nav_nodes.iter().choose(&mut rng)
-        else {
-            return;
-        };
-
-        let direction = Vec3::new(
-            rng.gen::<f32>() - 0.5,
-            rng.gen::<f32>() - 0.5,
-            rng.gen::<f32>() - 0.5,
-        )
-        .normalize();
-        let color_primary_kind = AntColorKind::new_random(&mut rng);
-        let color_secondary_kind =
-            AntColorKind::new_random_from_primary(&mut rng, &color_primary_kind);
-        let speed = 40.;
-        // let scale = rng.gen::<f32>() + 0.5;
-        let scale = 1.; // TODO
-        LiveAntBundle::spawn_on_nav_node(
-            &mut commands,
-            direction,
-            speed,
-            scale,
-            color_primary_kind,
-            color_secondary_kind,
-            nav_node_entity,
-            nav_node,
-            nav_node_pos,
-            entities_holder,
-            entities_holder_pos,
-            &mut rng,
-            AntGoal {
-                job: Job::Food,
-                holds: 0.,
-            },
-        );
-    }
-
-    for _ in 0..20 {
-        let Some((nav_node_entity, nav_node_pos, nav_node)) = nav_nodes.iter().choose(&mut rng)
-        else {
-            return;
-        };
-
-        let direction = Vec3::new(
-            rng.gen::<f32>() - 0.5,
-            rng.gen::<f32>() - 0.5,
-            rng.gen::<f32>() - 0.5,
-        )
-        .normalize();
-        let color_primary_kind = AntColorKind::new_random(&mut rng);
-        let color_secondary_kind =
-            AntColorKind::new_random_from_primary(&mut rng, &color_primary_kind);
-        let speed = 40.;
-        // let scale = rng.gen::<f32>() + 0.5;
-        let scale = 1.; // TODO
-        ZombAntBundle::spawn_on_nav_node(
-            &mut commands,
-            direction,
-            speed,
-            scale,
-            color_primary_kind,
-            color_secondary_kind,
-            nav_node_entity,
-            nav_node,
-            nav_node_pos,
-            entities_holder,
-            entities_holder_pos,
-            &mut rng,
-            AntGoal {
-                job: Job::Thief,
-                holds: 0.,
-            },
-        );
-    }
-    // }
-}
-
 pub fn spawn_explosions(
     mut commands: Commands,
     mut mouse_events: EventReader<MouseButtonInput>,
@@ -10,14 +10,30 @@ use itertools::Itertools;
 use last_of_ants::{
     components::{
         ants::{
-            goal::{AntGoal, Metrics},
+            alarm::AlarmConfig,
+            boss::BossConfig,
+            colony_queen::ColonyQueenConfig,
+            crowd::CrowdConfig,
+            dead_ants::DecompositionConfig,
+            evasion::EvasionConfig,
+            goal::Metrics,
+            infection::InfectionConfig,
             job::Job,
-            live_ants::{LiveAnt, LiveAntBundle},
-            movement::position::debug_ants,
-            zombants::{spawn_zombant_queen, ZombAntBundle},
+            life::LifeConfig,
+            live_ants::LiveAnt,
+            lod::LodConfig,
+            movement::{
+                direction::SensingConfig, falling::FallConfig, nav_graph::LocomotionMode,
+                position::debug_ants, steering::SteeringConfig,
+            },
+            soldiers::SoldierConfig,
+            spawner::{AntArchetype, AntSpawner},
+            zombants::{spawn_zombant_queen, ZombAntQueenConfig},
             AntColorKind,
         },
+        cocoons::CocoonConfig,
         nav_mesh::{debug_nav_mesh, NavNode},
+        nursery::Nursery,
         pheromones::{
             concentrations::PheromoneConcentrations, gradients::PheromoneGradients,
             PheromoneConfig, PheromoneKind, N_PHEROMONE_KINDS,
@@ -25,11 +41,11 @@ use last_of_ants::{
     },
     helpers::{on_key_just_pressed, toggle_on_key, toggle_physics_debug},
     render::{MainCamera2d, MainCamera2dBundle},
-    resources::clues::Clues,
+    resources::{clues::Clues, colony::ColonyConfig, job_allocation::JobAllocation},
     ui::ui_clues::UiCluesPlugin,
     GamePlugin,
 };
-use rand::{seq::IteratorRandom, Rng};
+use rand::seq::IteratorRandom;
 
 fn main() {
     App::new()
@@ -43,6 +59,30 @@ fn main() {
             ResourceInspectorPlugin::<PheromoneConfig>::default(),
             ResourceInspectorPlugin::<Metrics>::default(),
         ))
+        // Plugin tuples are limited in size
+        .add_plugins((
+            ResourceInspectorPlugin::<ColonyConfig>::default(),
+            ResourceInspectorPlugin::<JobAllocation>::default(),
+            ResourceInspectorPlugin::<Nursery>::default(),
+            ResourceInspectorPlugin::<CocoonConfig>::default(),
+            ResourceInspectorPlugin::<ColonyQueenConfig>::default(),
+            ResourceInspectorPlugin::<LifeConfig>::default(),
+            ResourceInspectorPlugin::<InfectionConfig>::default(),
+            ResourceInspectorPlugin::<ZombAntQueenConfig>::default(),
+            ResourceInspectorPlugin::<EvasionConfig>::default(),
+            ResourceInspectorPlugin::<BossConfig>::default(),
+            ResourceInspectorPlugin::<SoldierConfig>::default(),
+            ResourceInspectorPlugin::<AlarmConfig>::default(),
+            ResourceInspectorPlugin::<DecompositionConfig>::default(),
+        ))
+        .add_plugins((
+            ResourceInspectorPlugin::<SensingConfig>::default(),
+            ResourceInspectorPlugin::<SteeringConfig>::default(),
+            ResourceInspectorPlugin::<CrowdConfig>::default(),
+            ResourceInspectorPlugin::<LocomotionMode>::default(),
+            ResourceInspectorPlugin::<FallConfig>::default(),
+            ResourceInspectorPlugin::<LodConfig>::default(),
+        ))
         .add_systems(Startup, setup)
         .add_systems(
             Update,
@@ -104,96 +144,27 @@ fn update_text_counters(
     texts.single_mut().sections[2].value = format!("Ants: {num_ants}\n");
 }
 
-fn spawn_ants_on_navmesh(
-    mut commands: Commands,
-    nav_nodes: Query<(Entity, &GlobalTransform, &NavNode)>,
-    level: Query<&Children, With<LevelIid>>,
-    named_transform: Query<(Entity, &Name, &GlobalTransform)>,
-) {
+fn spawn_ants_on_navmesh(mut commands: Commands, nav_nodes: Query<Entity, With<NavNode>>) {
     let mut rng = rand::thread_rng();
-    let level_children = level.single();
-    let (entities_holder, _, entities_holder_pos) = level_children
-        .iter()
-        .filter_map(|child| named_transform.get(*child).ok())
-        .find(|(_, name, _)| name.as_str() == "Entities")
-        .unwrap();
 
     for _ in 0..30 {
-        let Some((nav_node_entity, nav_node_pos, nav_node)) = nav_nodes.iter().choose(&mut rng)
-        else {
+        let Some(nav_node_entity) = nav_nodes.iter().choose(&mut rng) else {
             return;
         };
-
-        let direction = Vec3::new(
-            rng.gen::<f32>() - 0.5,
-            rng.gen::<f32>() - 0.5,
-            rng.gen::<f32>() - 0.5,
-        )
-        .normalize();
-        let color_primary_kind = AntColorKind::YELLOW;
-        let color_secondary_kind = AntColorKind::YELLOW;
-        // let color_primary_kind = AntColorKind::BLACK;
-        // let color_secondary_kind = AntColorKind::BLACK;
-        // let scale = rng.gen::<f32>() + 0.5;
-        let scale = 1.; // TODO
-        let speed = 40.;
-        let goal = AntGoal {
-            job: Job::Food,
-            holds: 0.,
-        };
-        LiveAntBundle::spawn_on_nav_node(
-            &mut commands,
-            direction,
-            speed,
-            scale,
-            color_primary_kind,
-            color_secondary_kind,
-            nav_node_entity,
-            nav_node,
-            nav_node_pos,
-            entities_holder,
-            entities_holder_pos,
-            &mut rng,
-            goal,
-        );
-        // .insert(MovementGoal(_id));
+        AntSpawner::new(AntArchetype::Live, nav_node_entity)
+            .job(Job::Food)
+            .colors(AntColorKind::YELLOW, Some(AntColorKind::YELLOW))
+            .spawn(&mut commands);
     }
 
     for _ in 0..10 {
-        let Some((nav_node_entity, nav_node_pos, nav_node)) = nav_nodes.iter().choose(&mut rng)
-        else {
+        let Some(nav_node_entity) = nav_nodes.iter().choose(&mut rng) else {
             return;
         };
-
-        let direction = Vec3::new(
-            rng.gen::<f32>() - 0.5,
-            rng.gen::<f32>() - 0.5,
-            rng.gen::<f32>() - 0.5,
-        )
-        .normalize();
-        let color_primary_kind = AntColorKind::WHITE;
-        let color_secondary_kind = AntColorKind::WHITE;
-        let scale = 1.; // TODO
-        let speed = 40.;
-        let goal = AntGoal {
-            job: Job::Thief,
-            holds: 0.,
-        };
-        ZombAntBundle::spawn_on_nav_node(
-            &mut commands,
-            direction,
-            speed,
-            scale,
-            color_primary_kind,
-            color_secondary_kind,
-            nav_node_entity,
-            nav_node,
-            nav_node_pos,
-            entities_holder,
-            entities_holder_pos,
-            &mut rng,
-            goal,
-        );
+        AntSpawner::new(AntArchetype::Zombant, nav_node_entity)
+            .job(Job::Thief)
+            .colors(AntColorKind::WHITE, Some(AntColorKind::WHITE))
+            .spawn(&mut commands);
     }
 }
 
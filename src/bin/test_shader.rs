@@ -8,7 +8,7 @@ use last_of_ants::{
         goal::AntGoal,
         live_ants::LiveAnt,
         movement::{position::AntPositionKind, AntMovement},
-        AntColorKind, AntStyle,
+        AntColorKind, AntStyle, CarriedItem,
     },
     helpers::on_key_just_pressed,
     render::render_ant::{
@@ -93,6 +93,9 @@ fn spawn(mut commands: Commands) {
                 },
                 speed: 30.,
                 direction,
+                heading: direction,
+                angular_velocity: 0.,
+                avoidance: Vec3::ZERO,
                 current_node: (Entity::PLACEHOLDER, GlobalTransform::default()),
                 goal: AntGoal::default(),
                 last_direction_update: 0.,
@@ -104,6 +107,9 @@ fn spawn(mut commands: Commands) {
                 color_secondary,
                 color_secondary_kind,
                 animation_phase: rng.gen::<f32>() * 2. * PI,
+                halo: 0.,
+                decay: 0.,
+                carried: CarriedItem::Nothing,
             },
         ));
     }
@@ -115,7 +121,7 @@ fn move_forward(
     inputs: Res<Input<KeyCode>>,
 ) {
     for (mut ant, mut transform) in ants.iter_mut() {
-        let forward = ant.direction;
+        let forward = ant.heading;
         if inputs.pressed(KeyCode::W) {
             transform.translation += forward * 50.0 * time.delta_seconds();
         } else if inputs.pressed(KeyCode::S) {
@@ -135,5 +141,6 @@ fn move_forward(
         // transform.rotate_local_z(angle * PI / 8. * time.delta_seconds());
         // let (sin, cos) = angle.sin_cos();
         ant.direction = Mat3::from_angle(angle) * ant.direction;
+        ant.heading = ant.direction;
     }
 }
@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 use bevy_inspector_egui::quick::ResourceInspectorPlugin;
 use last_of_ants::{
+    components::ants::{zombants::ZombAntQueen, AntColorKind, AntStyle},
     helpers::on_key_just_pressed,
     render::{render_ant::AntMaterialPlugin, MainCamera2dBundle},
     resources::clues::{clues_receive_events, found_clue, ClueEvent, Clues},
@@ -29,4 +30,15 @@ fn main() {
 
 fn setup(mut commands: Commands) {
     commands.spawn(MainCamera2dBundle::default());
+    // Queen disguises for the clues, not rendered
+    let mut rng = rand::thread_rng();
+    for _ in 0..2 {
+        let color_primary_kind = AntColorKind::new_random(&mut rng);
+        let color_secondary_kind =
+            AntColorKind::new_random_from_primary(&mut rng, &color_primary_kind);
+        commands.spawn((
+            ZombAntQueen::default(),
+            AntStyle::new(1., color_primary_kind, color_secondary_kind, &mut rng),
+        ));
+    }
 }
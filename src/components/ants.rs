@@ -1,26 +1,93 @@
-use bevy::prelude::*;
+use std::{f32::consts::PI, str::FromStr};
+
+use bevy::{prelude::*, utils::HashSet};
 use bevy_rapier2d::prelude::*;
 use rand::{rngs::ThreadRng, seq::SliceRandom, Rng};
 
-use crate::render::player_animation::Explosion;
+use crate::{
+    components::nursery::Carried, render::player_animation::Explosion, ANT_SIZE, ANT_WALL_CLIPPING,
+};
 
+pub mod alarm;
+pub mod boss;
+pub mod caste;
+pub mod colony_queen;
+pub mod crowd;
 pub mod dead_ants;
+pub mod evasion;
 pub mod goal;
+pub mod infection;
 pub mod job;
+pub mod life;
 pub mod live_ants;
+pub mod lod;
 pub mod movement;
+pub mod soldiers;
+pub mod spawner;
 pub mod zombants;
-use self::dead_ants::DeadAntBundle;
+use self::{
+    dead_ants::{kill_ant, DeadAnt},
+    job::Job,
+    movement::AntMovement,
+    zombants::ZombAntQueen,
+};
 
 #[derive(Debug, Clone, Copy, Component, Reflect)]
 pub struct AntStyle {
-    /// TODO: Scaled is only used for rendering for now
+    /// Size relative to [ANT_SIZE], see [caste::Caste::scale]
     pub scale: f32,
     pub color_primary: Color,
     pub color_primary_kind: AntColorKind,
     pub color_secondary: Color,
     pub color_secondary_kind: AntColorKind,
     pub animation_phase: f32,
+    /// Intensity of the halo drawn around the ant, between 0 and 1
+    pub halo: f32,
+    /// Decomposition of a dead ant, between 0 (fresh) and 1 (gone)
+    pub decay: f32,
+    pub carried: CarriedItem,
+}
+
+/// Item drawn in the mandibles of an ant
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum CarriedItem {
+    #[default]
+    Nothing = 0,
+    Food = 1,
+    /// Food taken from the storage by a thief
+    StolenFood = 2,
+    /// Dead ant carried to the midden, its own entity is hidden meanwhile
+    Corpse = 3,
+}
+
+impl AntStyle {
+    pub fn new(
+        scale: f32,
+        color_primary_kind: AntColorKind,
+        color_secondary_kind: AntColorKind,
+        rng: &mut ThreadRng,
+    ) -> Self {
+        Self {
+            scale,
+            color_primary: color_primary_kind.generate_color(rng),
+            color_primary_kind,
+            color_secondary: color_secondary_kind.generate_color(rng),
+            color_secondary_kind,
+            animation_phase: rng.gen::<f32>() * 2. * PI,
+            halo: 0.,
+            decay: 0.,
+            carried: CarriedItem::Nothing,
+        }
+    }
+
+    pub fn size(&self) -> Vec2 {
+        ANT_SIZE * self.scale
+    }
+
+    /// Number of pixels the ant should be clipping in a wall when moving on it
+    pub fn wall_clipping(&self) -> f32 {
+        ANT_WALL_CLIPPING * self.scale
+    }
 }
 
 /// Kind of color, used to give the player clues
@@ -80,21 +147,54 @@ impl AntColorKind {
     }
 }
 
+impl FromStr for AntColorKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "black" => Ok(Self::BLACK),
+            "red" => Ok(Self::RED),
+            "brown" => Ok(Self::BROWN),
+            "green" => Ok(Self::GREEN),
+            "yellow" => Ok(Self::YELLOW),
+            "white" => Ok(Self::WHITE),
+            _ => Err(format!("unknown color `{s}`")),
+        }
+    }
+}
+
+/// Kill ants caught in an explosion, the Zomb'Ant Queen is handled by [boss::update_queen_boss]
 pub fn ant_explosion_collision(
     mut commands: Commands,
-    ants: Query<(Entity, &CollidingEntities, &Parent, &Transform, &AntStyle)>,
+    ants: Query<(Entity, &CollidingEntities), Without<ZombAntQueen>>,
     explosions: Query<(), With<Explosion>>,
 ) {
-    for (ant, colliding_entities, parent, ant_transform, ant_style) in ants.iter() {
+    for (ant, colliding_entities) in ants.iter() {
         for colliding_entity in colliding_entities.iter() {
             if explosions.contains(colliding_entity) {
-                // Despawn ant
-                commands.entity(ant).remove_parent().despawn();
-                // Spawn dead ant
-                commands
-                    .spawn(DeadAntBundle::new(*ant_transform, *ant_style))
-                    .set_parent(parent.get());
+                kill_ant(&mut commands, ant);
+                break;
             }
         }
     }
 }
+
+/// Show what the ants carry, so thieves can be spotted
+pub fn update_ant_carried_items(
+    mut ants: Query<(Entity, &AntMovement, &mut AntStyle)>,
+    carried_corpses: Query<&Carried, With<DeadAnt>>,
+) {
+    let corpse_carriers: HashSet<Entity> = carried_corpses.iter().map(|c| c.carrier).collect();
+    for (ant, ant_movement, mut ant_style) in ants.iter_mut() {
+        let goal = ant_movement.goal;
+        let carried = match goal.job {
+            Job::Storage if goal.holds > 0. => CarriedItem::Food,
+            Job::Offering if goal.holds > 0. => CarriedItem::StolenFood,
+            Job::Bury if corpse_carriers.contains(&ant) => CarriedItem::Corpse,
+            _ => CarriedItem::Nothing,
+        };
+        if ant_style.carried != carried {
+            ant_style.carried = carried;
+        }
+    }
+}
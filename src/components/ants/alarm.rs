@@ -0,0 +1,143 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{
+        ants::{
+            dead_ants::DeadAnt,
+            job::Job,
+            live_ants::LiveAnt,
+            lod::{lod_delta, OffScreen},
+            movement::AntMovement,
+        },
+        pheromones::{
+            concentrations::PheromoneConcentrations, gradients::PheromoneGradients,
+            PheromoneConfig, PheromoneKind,
+        },
+    },
+    render::player_animation::Explosion,
+    resources::nav_mesh_lut::NavMeshLUT,
+};
+
+/// Live ant fleeing an alarm
+#[derive(Debug, Clone, Copy, Component, Reflect)]
+pub struct Panic {
+    /// Seconds before the ant calms down
+    pub remaining: f32,
+    /// Speed of the ant before it panicked
+    pub speed: f32,
+}
+
+#[derive(Debug, Clone, Resource, Reflect)]
+pub struct AlarmConfig {
+    /// Concentration of [PheromoneKind::Alarm] above which ants panic
+    pub panic_threshold: f32,
+    pub panic_duration: f32,
+    /// Speed multiplier while panicking
+    pub panic_speed_factor: f32,
+}
+
+impl Default for AlarmConfig {
+    fn default() -> Self {
+        Self {
+            panic_threshold: 1.,
+            panic_duration: 5.,
+            panic_speed_factor: 2.,
+        }
+    }
+}
+
+/// Emit an alarm pulse where explosions happen and where ants die
+pub fn update_alarm_sources(
+    explosions: Query<&Transform, Added<Explosion>>,
+    dead_ants: Query<(&Transform, &Parent), Added<DeadAnt>>,
+    holders: Query<&GlobalTransform>,
+    mut nodes: Query<&mut PheromoneConcentrations>,
+    navmesh_lut: Res<NavMeshLUT>,
+    phcfg: Res<PheromoneConfig>,
+) {
+    // Dead ants are only just spawned, their global transform is not computed yet
+    let dead_ants_pos = dead_ants.iter().filter_map(|(transform, parent)| {
+        holders
+            .get(parent.get())
+            .ok()
+            .map(|holder| holder.transform_point(transform.translation))
+    });
+    let pulses = explosions
+        .iter()
+        .map(|transform| transform.translation)
+        .chain(dead_ants_pos);
+    for pos in pulses {
+        let Some((node, _)) = navmesh_lut.get_tile_entity(pos.xy()) else {
+            continue;
+        };
+        if let Ok(mut pheromones) = nodes.get_mut(node) {
+            pheromones.concentrations[PheromoneKind::Alarm as usize] += phcfg.alarm_pulse;
+        }
+    }
+}
+
+/// Live ants smelling an alarm flee down its gradient, while patrols rush towards it
+pub fn update_alarm_response(
+    mut commands: Commands,
+    mut ants: Query<
+        (
+            Entity,
+            &mut AntMovement,
+            Option<&mut Panic>,
+            Option<&OffScreen>,
+        ),
+        With<LiveAnt>,
+    >,
+    nodes: Query<(&PheromoneConcentrations, &PheromoneGradients)>,
+    config: Res<AlarmConfig>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    for (ant, mut ant_movement, panic, off_screen) in ants.iter_mut() {
+        let Some(dt) = lod_delta(off_screen, dt) else {
+            continue;
+        };
+        let Ok((concentrations, gradients)) = nodes.get(ant_movement.current_node.0) else {
+            continue;
+        };
+        let alarm = concentrations.concentrations[PheromoneKind::Alarm as usize];
+        let alarm_gradient = gradients.gradients[PheromoneKind::Alarm as usize];
+
+        let patrol = ant_movement.goal.job == Job::Patrol;
+        // Calm down before anything else, so ants turned into patrols while panicking recover
+        // their speed
+        let panicking = panic.is_some();
+        if let Some(mut panic) = panic {
+            if alarm > config.panic_threshold && !patrol {
+                panic.remaining = config.panic_duration;
+            }
+            panic.remaining -= dt;
+            if panic.remaining <= 0. {
+                ant_movement.speed = panic.speed;
+                commands.entity(ant).remove::<Panic>();
+                continue;
+            }
+        }
+
+        if patrol {
+            if alarm_gradient != Vec3::ZERO {
+                ant_movement.direction = alarm_gradient;
+            }
+            continue;
+        }
+
+        if !panicking {
+            if alarm <= config.panic_threshold {
+                continue;
+            }
+            commands.entity(ant).insert(Panic {
+                remaining: config.panic_duration,
+                speed: ant_movement.speed,
+            });
+            ant_movement.speed *= config.panic_speed_factor;
+        }
+        if alarm_gradient != Vec3::ZERO {
+            ant_movement.direction = -alarm_gradient;
+        }
+    }
+}
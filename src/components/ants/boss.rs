@@ -0,0 +1,169 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    components::{
+        ants::{
+            dead_ants::kill_ant,
+            evasion::{farthest_room, QueenEvasion},
+            job::Job,
+            movement::AntMovement,
+            zombants::{ZombAnt, ZombAntQueen},
+            AntStyle,
+        },
+        nav_mesh::{find_path, NavNode},
+        nursery::Nursery,
+        player::Player,
+    },
+    render::player_animation::Explosion,
+    TILE_SIZE,
+};
+
+/// Encounter with the Zomb'Ant Queen once the player gets close to her
+#[derive(Debug, Clone, Copy, Component, Reflect)]
+pub struct QueenBoss {
+    pub hit_points: u32,
+    /// The queen has been approached and shows her halo
+    pub revealed: bool,
+    /// Speed of the queen before she started fleeing, if she is fleeing
+    pub fleeing: Option<f32>,
+    last_hit: f32,
+    last_summon: f32,
+}
+
+impl QueenBoss {
+    pub fn new(config: &BossConfig) -> Self {
+        Self {
+            hit_points: config.hit_points,
+            revealed: false,
+            fleeing: None,
+            last_hit: f32::NEG_INFINITY,
+            last_summon: f32::NEG_INFINITY,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Resource, Reflect)]
+pub struct BossConfig {
+    /// Number of explosions needed to kill the queen
+    pub hit_points: u32,
+    /// Distance from the player under which the queen reveals herself
+    pub reveal_radius: f32,
+    /// Distance under which zombants come to protect the queen
+    pub summon_radius: f32,
+    /// Seconds between two summons
+    pub summon_period: f32,
+    /// Seconds after a hit during which explosions have no effect, the queen flees meanwhile
+    pub invulnerability: f32,
+    /// Speed multiplier while fleeing
+    pub flee_speed_factor: f32,
+}
+
+impl Default for BossConfig {
+    fn default() -> Self {
+        Self {
+            hit_points: 3,
+            reveal_radius: 6. * TILE_SIZE,
+            summon_radius: 20. * TILE_SIZE,
+            summon_period: 5.,
+            invulnerability: 4.,
+            flee_speed_factor: 2.,
+        }
+    }
+}
+
+pub fn update_queen_boss(
+    mut commands: Commands,
+    mut queens: Query<
+        (
+            Entity,
+            &mut QueenBoss,
+            &mut AntMovement,
+            &mut AntStyle,
+            &mut QueenEvasion,
+            &CollidingEntities,
+            &GlobalTransform,
+        ),
+        With<ZombAntQueen>,
+    >,
+    mut zombants: Query<
+        (&mut AntMovement, &GlobalTransform),
+        (With<ZombAnt>, Without<ZombAntQueen>),
+    >,
+    explosions: Query<(), With<Explosion>>,
+    players: Query<&GlobalTransform, With<Player>>,
+    nav_nodes: Query<&NavNode>,
+    nursery: Res<Nursery>,
+    config: Res<BossConfig>,
+    time: Res<Time>,
+) {
+    let elapsed = time.elapsed_seconds();
+    let player_pos = players.get_single().ok().map(|p| p.translation().xy());
+    let neighbors = |node: Entity| {
+        nav_nodes
+            .get(node)
+            .map(|nav_node| nav_node.neighbors())
+            .unwrap_or_default()
+    };
+
+    for (
+        queen,
+        mut boss,
+        mut queen_movement,
+        mut queen_style,
+        mut evasion,
+        colliding_entities,
+        queen_global_transform,
+    ) in queens.iter_mut()
+    {
+        let queen_pos = queen_global_transform.translation().xy();
+        let vulnerable = elapsed - boss.last_hit > config.invulnerability;
+        let hit = vulnerable
+            && colliding_entities
+                .iter()
+                .any(|entity| explosions.contains(entity));
+
+        // Reveal
+        if hit || player_pos.is_some_and(|p| p.distance(queen_pos) < config.reveal_radius) {
+            boss.revealed = true;
+        }
+        if !boss.revealed {
+            continue;
+        }
+        queen_style.halo = 1.;
+
+        // Summon the zombants around, they follow her pheromone
+        if elapsed - boss.last_summon > config.summon_period {
+            boss.last_summon = elapsed;
+            for (mut zombant_movement, zombant_transform) in zombants.iter_mut() {
+                if zombant_transform.translation().xy().distance(queen_pos) < config.summon_radius {
+                    zombant_movement.goal.job = Job::Offering;
+                }
+            }
+        }
+
+        // Stop fleeing
+        if vulnerable {
+            if let Some(speed) = boss.fleeing.take() {
+                queen_movement.speed = speed;
+            }
+        }
+
+        // Take hits
+        if !hit {
+            continue;
+        }
+        boss.hit_points = boss.hit_points.saturating_sub(1);
+        boss.last_hit = elapsed;
+        if boss.hit_points == 0 {
+            kill_ant(&mut commands, queen);
+            continue;
+        }
+        if let Some(room) = farthest_room(&nursery, player_pos) {
+            evasion.path = find_path(queen_movement.current_node.0, room.nav_node, neighbors)
+                .unwrap_or_default();
+        }
+        boss.fleeing = Some(queen_movement.speed);
+        queen_movement.speed *= config.flee_speed_factor;
+    }
+}
@@ -0,0 +1,106 @@
+use std::str::FromStr;
+
+use bevy::prelude::*;
+
+use super::job::Job;
+
+/// Physical caste of an ant
+///
+/// The caste sets the default size ([AntStyle::scale](super::AntStyle::scale)) from which the
+/// collider, wall clipping and rendering are derived, along with the speed and how much the ant
+/// can carry.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Component, Reflect)]
+pub enum Caste {
+    #[default]
+    MinorWorker,
+    MajorWorker,
+    Soldier,
+    Scout,
+}
+
+impl Caste {
+    pub fn scale(&self) -> f32 {
+        match self {
+            Caste::MinorWorker => 1.,
+            Caste::MajorWorker => 1.3,
+            Caste::Soldier => 1.5,
+            Caste::Scout => 0.8,
+        }
+    }
+
+    /// Multiplier applied to the base speed of the ant
+    pub fn speed_factor(&self) -> f32 {
+        match self {
+            Caste::MinorWorker => 1.,
+            Caste::MajorWorker => 0.9,
+            Caste::Soldier => 0.8,
+            Caste::Scout => 1.4,
+        }
+    }
+
+    /// Response threshold of the caste to the stimulus of a task, see
+    /// [JobAllocation](crate::resources::job_allocation::JobAllocation)
+    ///
+    /// Low thresholds make the caste eager to take the task.
+    pub fn threshold(&self, task: Job) -> f32 {
+        match (self, task) {
+            (Caste::MinorWorker, Job::Food) => 0.2,
+            (Caste::MajorWorker, Job::Food) => 0.3,
+            (Caste::Soldier, Job::Food) => 1.,
+            (Caste::Scout, Job::Food) => 0.5,
+            (Caste::MinorWorker, Job::Wander) => 0.6,
+            (Caste::MajorWorker, Job::Wander) => 0.4,
+            (Caste::Soldier, Job::Wander) => 0.1,
+            (Caste::Scout, Job::Wander) => 0.1,
+            (Caste::MinorWorker, Job::Nurse) => 0.1,
+            (Caste::MajorWorker, Job::Nurse) => 0.5,
+            (Caste::Scout, Job::Nurse) => 0.8,
+            (Caste::MajorWorker, Job::Patrol) => 0.8,
+            (Caste::Soldier, Job::Patrol) => 0.05,
+            (Caste::Scout, Job::Patrol) => 0.6,
+            (Caste::MinorWorker, Job::Undertaker) => 0.5,
+            (Caste::MajorWorker, Job::Undertaker) => 0.2,
+            (Caste::Scout, Job::Undertaker) => 0.7,
+            _ => f32::INFINITY,
+        }
+    }
+
+    /// Quantity of food picked up at once, see [AntGoal::holds](super::goal::AntGoal::holds)
+    pub fn capacity(&self) -> f32 {
+        match self {
+            Caste::MinorWorker => 1.,
+            Caste::MajorWorker => 2.,
+            Caste::Soldier => 0.5,
+            Caste::Scout => 0.5,
+        }
+    }
+}
+
+impl FromStr for Caste {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "minor" | "minorworker" | "minor_worker" => Ok(Self::MinorWorker),
+            "major" | "majorworker" | "major_worker" => Ok(Self::MajorWorker),
+            "soldier" => Ok(Self::Soldier),
+            "scout" => Ok(Self::Scout),
+            _ => Err(format!("unknown caste `{s}`")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_castes() {
+        assert_eq!("minor".parse(), Ok(Caste::MinorWorker));
+        assert_eq!("MinorWorker".parse(), Ok(Caste::MinorWorker));
+        assert_eq!("major_worker".parse(), Ok(Caste::MajorWorker));
+        assert_eq!("Soldier".parse(), Ok(Caste::Soldier));
+        assert_eq!("scout".parse(), Ok(Caste::Scout));
+        assert!("queen".parse::<Caste>().is_err());
+    }
+}
@@ -0,0 +1,144 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::LdtkEntity;
+use rand::{seq::IteratorRandom, thread_rng};
+
+use crate::{
+    components::{
+        ants::{
+            movement::AntMovement,
+            spawner::{AntArchetype, AntSpawner},
+            AntColorKind,
+        },
+        cocoons::CocoonBundle,
+        nursery::Nursery,
+        object::{take_from_storage, Object},
+        pheromones::{concentrations::PheromoneConcentrations, PheromoneConfig, PheromoneKind},
+    },
+    resources::nav_mesh_lut::NavMeshLUT,
+    AppState, GameOverCause,
+};
+
+/// The legitimate queen of the colony, laying cocoons from the stored food
+#[derive(Default, Debug, Clone, Copy, Component, Reflect)]
+pub struct ColonyQueen {
+    /// Progress towards the next cocoon, laid when it reaches 1
+    pub laying: f32,
+}
+
+#[derive(Debug, Default, Clone, Copy, Component, Reflect, LdtkEntity)]
+pub struct ColonyQueenSpawnPoint {}
+
+#[derive(Debug, Clone, Resource, Reflect)]
+pub struct ColonyQueenConfig {
+    pub scale: f32,
+    /// Cocoons laid per second when the storage is full
+    pub max_laying_rate: f32,
+    /// Stored food at which the queen lays at half of the max rate
+    pub half_rate_food: f32,
+    /// Food taken from the storage for each cocoon
+    pub cocoon_cost: f32,
+}
+
+impl Default for ColonyQueenConfig {
+    fn default() -> Self {
+        Self {
+            scale: 2.,
+            max_laying_rate: 0.1,
+            half_rate_food: 50.,
+            cocoon_cost: 2.,
+        }
+    }
+}
+
+/// Spawn the colony queen in the queen chamber, or in the nursery if the level has none
+pub fn spawn_colony_queen(
+    mut commands: Commands,
+    spawn_points: Query<&Transform, With<ColonyQueenSpawnPoint>>,
+    nursery: Res<Nursery>,
+    nav_mesh_lut: Res<NavMeshLUT>,
+    config: Res<ColonyQueenConfig>,
+) {
+    let mut rng = thread_rng();
+    let nav_node = match spawn_points.iter().choose(&mut rng) {
+        Some(spawn_point_pos) => nav_mesh_lut
+            .get_tile_entity(spawn_point_pos.translation.xy())
+            .map(|(nav_node, _)| nav_node),
+        None => nursery.current().map(|room| room.nav_node),
+    };
+    let Some(nav_node) = nav_node else {
+        error!("There is no queen chamber for the colony queen on the map");
+        return;
+    };
+    AntSpawner::new(AntArchetype::ColonyQueen, nav_node)
+        .speed(0.)
+        .scale(config.scale)
+        .colors(AntColorKind::BROWN, Some(AntColorKind::YELLOW))
+        .spawn(&mut commands);
+}
+
+/// Lay cocoons at a rate depending on the stored food
+pub fn update_colony_queen(
+    mut commands: Commands,
+    mut queens: Query<(&mut ColonyQueen, &Transform, &GlobalTransform, &Parent)>,
+    mut objects: Query<&mut Object>,
+    nursery: Res<Nursery>,
+    config: Res<ColonyQueenConfig>,
+    time: Res<Time>,
+) {
+    let mut food: f32 = objects
+        .iter()
+        .filter(|object| object.kind == PheromoneKind::Storage)
+        .filter_map(|object| object.quantity)
+        .sum();
+    let rate = config.max_laying_rate * food / (food + config.half_rate_food);
+    for (mut queen, transform, global_transform, parent) in queens.iter_mut() {
+        queen.laying += rate * time.delta_seconds();
+        if queen.laying < 1. {
+            continue;
+        }
+        // Only take the food once there is enough for a whole cocoon
+        if food < config.cocoon_cost {
+            continue;
+        }
+        food -= take_from_storage(objects.iter_mut(), config.cocoon_cost);
+        queen.laying -= 1.;
+        // The cocoon belongs to the closest room, nurses will move it to the nursery if needed
+        let pos = global_transform.translation().xy();
+        let room = nursery
+            .rooms
+            .iter()
+            .min_by(|a, b| pos.distance(a.center).total_cmp(&pos.distance(b.center)))
+            .map(|room| room.room)
+            .unwrap_or_default();
+        let mut cocoon = CocoonBundle::new(false, room);
+        cocoon.cocoon.laid = true;
+        cocoon.material.transform.translation = transform.translation.xy().extend(0.);
+        commands.spawn(cocoon).set_parent(parent.get());
+    }
+}
+
+/// The queen attracts the nurses, and killing her ends the game
+pub fn update_colony_queen_source(
+    queens: Query<&AntMovement, With<ColonyQueen>>,
+    mut nodes: Query<&mut PheromoneConcentrations>,
+    phcfg: Res<PheromoneConfig>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut game_over_cause: ResMut<GameOverCause>,
+    mut had_queen: Local<bool>,
+) {
+    if queens.is_empty() {
+        // Levels without a queen cannot be lost
+        if *had_queen {
+            *game_over_cause = GameOverCause::ColonyQueenKilled;
+            next_state.set(AppState::GameOver);
+        }
+        return;
+    }
+    *had_queen = true;
+    for queen_movement in queens.iter() {
+        let Ok(mut pheromones) = nodes.get_mut(queen_movement.current_node.0) else {
+            continue;
+        };
+        pheromones.concentrations[PheromoneKind::Queen as usize] += phcfg.queen_source;
+    }
+}
@@ -0,0 +1,134 @@
+use std::f32::consts::PI;
+
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    components::{
+        ants::{
+            lod::OffScreen,
+            movement::{
+                direction::turning_axis, position::AntPositionKind, steering::project_on_surface,
+                AntMovement,
+            },
+        },
+        nav_mesh::NavNode,
+    },
+    ANT_SIZE,
+};
+
+/// How ants avoid each other on crowded trails
+#[derive(Debug, Clone, Resource, Reflect)]
+pub struct CrowdConfig {
+    /// Distance under which ants push each other away
+    pub separation_radius: f32,
+    pub separation_weight: f32,
+    /// Number of ants on a node and its neighbors above which ants keep to their right
+    pub busy_threshold: usize,
+    pub lane_weight: f32,
+    /// Distance under which an ant coming the other way makes an ant step aside
+    pub head_on_radius: f32,
+    /// Half angle in which ants are considered as coming straight at each other, in radians
+    pub head_on_angle: f32,
+    pub head_on_weight: f32,
+}
+
+impl Default for CrowdConfig {
+    fn default() -> Self {
+        Self {
+            separation_radius: ANT_SIZE.x,
+            separation_weight: 1.,
+            busy_threshold: 6,
+            lane_weight: 0.3,
+            head_on_radius: 2. * ANT_SIZE.y,
+            head_on_angle: PI / 4.,
+            head_on_weight: 1.,
+        }
+    }
+}
+
+/// Ant as seen by the other ants around it
+#[derive(Debug, Clone, Copy)]
+struct Neighbor {
+    entity: Entity,
+    position: Vec3,
+    heading: Vec3,
+    position_kind: AntPositionKind,
+}
+
+fn same_surface(a: AntPositionKind, b: AntPositionKind) -> bool {
+    std::mem::discriminant(&a) == std::mem::discriminant(&b)
+}
+
+/// Push ants away from each other, keep them to their right on busy trails, and make them step
+/// aside when they meet head-on
+///
+/// Ants are hashed by the node they stand on, so only the ants on the same and neighboring nodes
+/// are compared
+pub fn update_crowd_avoidance(
+    mut ants: Query<(Entity, &mut AntMovement, &GlobalTransform), Without<OffScreen>>,
+    nav_nodes: Query<&NavNode>,
+    config: Res<CrowdConfig>,
+) {
+    let mut ants_by_node = HashMap::<Entity, Vec<Neighbor>>::new();
+    for (entity, ant_movement, ant_transform) in ants.iter() {
+        ants_by_node
+            .entry(ant_movement.current_node.0)
+            .or_default()
+            .push(Neighbor {
+                entity,
+                position: ant_transform.translation(),
+                heading: ant_movement.heading,
+                position_kind: ant_movement.position_kind,
+            });
+    }
+    let head_on_cos = config.head_on_angle.cos();
+
+    for (entity, mut ant_movement, ant_transform) in ants.iter_mut() {
+        let node = ant_movement.current_node.0;
+        let position_kind = ant_movement.position_kind;
+        let position = ant_transform.translation();
+        let heading = project_on_surface(ant_movement.heading, position_kind).normalize_or_zero();
+        let right = heading.cross(turning_axis(position_kind));
+
+        let nodes = nav_nodes
+            .get(node)
+            .map(|nav_node| nav_node.neighbors())
+            .unwrap_or_default()
+            .into_iter()
+            .chain([node]);
+        let mut crowd = 0;
+        let mut separation = Vec3::ZERO;
+        let mut head_on = false;
+        for other in nodes.filter_map(|node| ants_by_node.get(&node)).flatten() {
+            crowd += 1;
+            if other.entity == entity || !same_surface(other.position_kind, position_kind) {
+                continue;
+            }
+            let offset = project_on_surface(other.position - position, position_kind);
+            let distance = offset.length();
+            if distance < config.separation_radius {
+                separation -=
+                    offset.normalize_or_zero() * (1. - distance / config.separation_radius);
+            }
+            if distance < config.head_on_radius
+                && offset.normalize_or_zero().dot(heading) > head_on_cos
+                && other.heading.normalize_or_zero().dot(heading) < -head_on_cos
+            {
+                head_on = true;
+            }
+        }
+
+        let mut avoidance = config.separation_weight * separation;
+        if crowd > config.busy_threshold {
+            avoidance += config.lane_weight * right;
+        }
+        // Both ants step to their right, so they pass each other
+        if head_on {
+            avoidance += config.head_on_weight * right;
+        }
+        let avoidance = project_on_surface(avoidance, position_kind);
+        if avoidance != ant_movement.avoidance {
+            ant_movement.avoidance = avoidance;
+        }
+    }
+}
@@ -2,18 +2,45 @@ use bevy::{prelude::*, render::view::RenderLayers};
 use bevy_rapier2d::prelude::*;
 
 use crate::{
-    components::ants::AntStyle,
+    components::ants::{AntStyle, CarriedItem},
     components::pheromones::{
         concentrations::PheromoneConcentrations, PheromoneConfig, PheromoneKind,
     },
+    components::{midden::Buried, nursery::Carried},
     render::render_ant::{AntMaterialBundle, ANT_MATERIAL_DEAD, ANT_MESH2D},
     resources::nav_mesh_lut::NavMeshLUT,
     ANT_SIZE, COLLISION_GROUP_DEAD_ANTS, COLLISION_GROUP_PLAYER, COLLISION_GROUP_WALLS,
     RENDERLAYER_ANTS,
 };
 
-#[derive(Debug, Component, Reflect)]
-pub struct DeadAnt;
+#[derive(Debug, Default, Component, Reflect)]
+pub struct DeadAnt {
+    /// Seconds since the death
+    pub age: f32,
+}
+
+#[derive(Debug, Clone, Resource, Reflect)]
+pub struct DecompositionConfig {
+    /// Seconds for a dead ant to fully decompose and disappear
+    pub duration: f32,
+    /// Seconds after the death before the body can settle
+    pub settle_delay: f32,
+    /// Speed under which a falling body is considered settled and stops being simulated
+    pub settle_speed: f32,
+    /// Maximum number of dead ants in the level, the oldest ones are removed first
+    pub max_dead_ants: usize,
+}
+
+impl Default for DecompositionConfig {
+    fn default() -> Self {
+        Self {
+            duration: 300.,
+            settle_delay: 1.,
+            settle_speed: 1.,
+            max_dead_ants: 500,
+        }
+    }
+}
 
 #[derive(Bundle)]
 pub struct DeadAntBundle {
@@ -28,22 +55,29 @@ pub struct DeadAntBundle {
     pub collision_groups: CollisionGroups,
     pub render_layers: RenderLayers,
     pub ccd: Ccd,
+    pub velocity: Velocity,
 }
 
 impl DeadAntBundle {
     pub fn new(ant_transform: Transform, ant_style: AntStyle) -> Self {
         Self {
-            dead_ant: DeadAnt,
-            ant_style,
+            dead_ant: DeadAnt::default(),
+            ant_style: AntStyle {
+                halo: 0.,
+                decay: 0.,
+                carried: CarriedItem::Nothing,
+                ..ant_style
+            },
             material: AntMaterialBundle {
                 mesh: ANT_MESH2D,
                 material: ANT_MATERIAL_DEAD,
-                transform: ant_transform,
+                transform: ant_transform.with_scale(Vec3::splat(ant_style.scale)),
                 ..default()
             },
             rigid_body: RigidBody::Dynamic,
             // Dead ants need to be smaller than ants, otherwise they are clipping with the player
             // when they spawn and are flung away
+            // The collider is already scaled along with the transform
             collider: Collider::cuboid(ANT_SIZE.x / 3., ANT_SIZE.y / 3.),
             collider_mass: ColliderMassProperties::Density(1.),
             render_layers: RENDERLAYER_ANTS,
@@ -54,21 +88,105 @@ impl DeadAntBundle {
             ccd: Ccd::enabled(),
             active_events: ActiveEvents::all(),
             active_collisions: ActiveCollisionTypes::all(),
+            velocity: Velocity::zero(),
         }
     }
 }
 
+/// Ant killed during the frame, it is replaced by a dead one by [replace_dying_ants]
+///
+/// Several systems can kill the same ant in a frame, the marker makes sure it only dies once.
+#[derive(Debug, Default, Clone, Copy, Component, Reflect)]
+pub struct Dying;
+
+/// Kill a live ant, see [Dying]
+pub fn kill_ant(commands: &mut Commands, ant: Entity) {
+    commands.entity(ant).insert(Dying);
+}
+
+/// Replace the ants killed during the previous frame with dead ones
+///
+/// It runs at the start of the frame, so no other system targets the despawned ants.
+pub fn replace_dying_ants(
+    mut commands: Commands,
+    ants: Query<(Entity, &Parent, &Transform, &AntStyle), With<Dying>>,
+) {
+    for (ant, parent, ant_transform, ant_style) in ants.iter() {
+        commands.entity(ant).remove_parent().despawn();
+        commands
+            .spawn(DeadAntBundle::new(*ant_transform, *ant_style))
+            .set_parent(parent.get());
+    }
+}
+
+/// Decomposing dead ants smell less and less
 pub fn update_dead_ants_deposit(
-    dead_ants: Query<&GlobalTransform, With<DeadAnt>>,
+    dead_ants: Query<
+        (&GlobalTransform, &AntStyle),
+        (With<DeadAnt>, Without<Buried>, Without<Carried>),
+    >,
     mut nodes: Query<&mut PheromoneConcentrations>,
     navmesh_lut: Res<NavMeshLUT>,
     phcfg: Res<PheromoneConfig>,
 ) {
-    for dead_ant_transform in dead_ants.iter() {
-        let (node, _) = navmesh_lut
-            .get_tile_entity(dead_ant_transform.translation().xy())
-            .unwrap();
+    for (dead_ant_transform, dead_ant_style) in dead_ants.iter() {
+        // Bodies can be flung out of the nest or into the ground
+        let Some((node, _)) = navmesh_lut.get_tile_entity(dead_ant_transform.translation().xy())
+        else {
+            continue;
+        };
         let mut pheromones = nodes.get_mut(node).unwrap();
-        pheromones.concentrations[PheromoneKind::DeadAnt as usize] += phcfg.dead_ant_deposit;
+        pheromones.concentrations[PheromoneKind::DeadAnt as usize] +=
+            phcfg.dead_ant_deposit * (1. - dead_ant_style.decay);
+    }
+}
+
+/// Decompose the dead ants, stop simulating the bodies that settled, and remove the decomposed
+/// ones along with the oldest ones above [DecompositionConfig::max_dead_ants]
+pub fn update_dead_ants_decomposition(
+    mut commands: Commands,
+    mut dead_ants: Query<(
+        Entity,
+        &mut DeadAnt,
+        &mut AntStyle,
+        &RigidBody,
+        &Velocity,
+        Option<&Carried>,
+    )>,
+    config: Res<DecompositionConfig>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    let mut remaining = Vec::new();
+    for (dead_ant, mut dead, mut style, rigid_body, velocity, carried) in dead_ants.iter_mut() {
+        dead.age += dt;
+        style.decay = (dead.age / config.duration).min(1.);
+        if style.decay >= 1. {
+            commands.entity(dead_ant).despawn_recursive();
+            continue;
+        }
+        remaining.push((dead.age, dead_ant));
+
+        if *rigid_body == RigidBody::Dynamic
+            && carried.is_none()
+            && dead.age > config.settle_delay
+            && velocity.linvel.length() < config.settle_speed
+        {
+            // The player walks through settled bodies instead of standing on a pile of them
+            commands.entity(dead_ant).insert((
+                RigidBody::Fixed,
+                Ccd::disabled(),
+                ActiveEvents::empty(),
+                CollisionGroups::new(COLLISION_GROUP_DEAD_ANTS, COLLISION_GROUP_WALLS),
+            ));
+        }
+    }
+
+    if remaining.len() > config.max_dead_ants {
+        remaining.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let excess = remaining.len() - config.max_dead_ants;
+        for (_, dead_ant) in remaining.into_iter().take(excess) {
+            commands.entity(dead_ant).despawn_recursive();
+        }
     }
 }
@@ -0,0 +1,163 @@
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use rand::{seq::SliceRandom, thread_rng};
+
+use crate::{
+    components::{
+        ants::{movement::AntMovement, zombants::ZombAntQueen},
+        nav_mesh::{find_path, NavNode},
+        nursery::{Nursery, NurseryRoom},
+        pheromones::{concentrations::PheromoneConcentrations, PheromoneKind},
+        player::Player,
+    },
+    resources::clues::ClueEvent,
+    TILE_SIZE,
+};
+
+/// Behaviour of the Zomb'Ant Queen hiding from the player
+#[derive(Debug, Default, Clone, Component, Reflect)]
+pub struct QueenEvasion {
+    /// Nodes to go through when relocating to another room
+    pub path: Vec<Entity>,
+    #[reflect(ignore)]
+    last_decision: f32,
+}
+
+#[derive(Debug, Clone, Resource, Reflect)]
+pub struct EvasionConfig {
+    /// Seconds between two choices of the next node
+    pub decision_period: f32,
+    /// Distance under which the queen flees the player
+    pub player_radius: f32,
+    pub player_weight: f32,
+    /// Weight of the [PheromoneKind::DeadAnt] concentration, read as danger
+    pub danger_weight: f32,
+    /// Weight of the number of ants on a node, to blend in the crowd
+    pub crowd_weight: f32,
+}
+
+impl Default for EvasionConfig {
+    fn default() -> Self {
+        Self {
+            decision_period: 0.5,
+            player_radius: 10. * TILE_SIZE,
+            player_weight: 10.,
+            danger_weight: 0.5,
+            crowd_weight: 1.,
+        }
+    }
+}
+
+/// Room to relocate to, far from the player
+pub fn farthest_room(nursery: &Nursery, player_pos: Option<Vec2>) -> Option<NurseryRoom> {
+    let mut rooms = nursery.rooms.clone();
+    rooms.shuffle(&mut thread_rng());
+    match player_pos {
+        Some(player_pos) => rooms.into_iter().max_by(|a, b| {
+            a.center
+                .distance(player_pos)
+                .total_cmp(&b.center.distance(player_pos))
+        }),
+        None => rooms.first().copied(),
+    }
+}
+
+/// Steer the queen away from the player and danger towards crowds, and relocate her to another
+/// room when a clue about her is found
+pub fn update_queen_evasion(
+    mut queens: Query<(Entity, &mut AntMovement, &mut QueenEvasion), With<ZombAntQueen>>,
+    ants: Query<&AntMovement, Without<ZombAntQueen>>,
+    nav_nodes: Query<(&NavNode, &GlobalTransform, &PheromoneConcentrations)>,
+    players: Query<&GlobalTransform, With<Player>>,
+    nursery: Res<Nursery>,
+    mut clue_events: EventReader<ClueEvent>,
+    config: Res<EvasionConfig>,
+    time: Res<Time>,
+) {
+    let clues_found: HashSet<Entity> = clue_events
+        .read()
+        .map(|event| match event {
+            ClueEvent::Found(queen) => *queen,
+        })
+        .collect();
+    let player_pos = players.get_single().ok().map(|p| p.translation().xy());
+    let neighbors = |node: Entity| {
+        nav_nodes
+            .get(node)
+            .map(|(nav_node, _, _)| nav_node.neighbors())
+            .unwrap_or_default()
+    };
+    let position = |node: Entity| {
+        nav_nodes
+            .get(node)
+            .ok()
+            .map(|(_, pos, _)| pos.translation())
+    };
+    let mut crowd = HashMap::<Entity, usize>::new();
+    for ant_movement in ants.iter() {
+        *crowd.entry(ant_movement.current_node.0).or_default() += 1;
+    }
+    let elapsed = time.elapsed_seconds();
+
+    for (queen, mut queen_movement, mut evasion) in queens.iter_mut() {
+        let current_node = queen_movement.current_node.0;
+        if clues_found.contains(&queen) {
+            if let Some(room) = farthest_room(&nursery, player_pos) {
+                evasion.path =
+                    find_path(current_node, room.nav_node, neighbors).unwrap_or_default();
+            }
+        }
+
+        if elapsed - evasion.last_decision < config.decision_period {
+            continue;
+        }
+        evasion.last_decision = elapsed;
+
+        // Follow the path, recomputing it if the queen strayed from it
+        if let Some(index) = evasion.path.iter().position(|node| *node == current_node) {
+            evasion.path.drain(..=index);
+        }
+        let next_node = match evasion.path.first() {
+            Some(next_node) if neighbors(current_node).contains(next_node) => Some(*next_node),
+            Some(_) => {
+                let goal = *evasion.path.last().unwrap();
+                evasion.path = find_path(current_node, goal, neighbors).unwrap_or_default();
+                evasion.path.first().copied()
+            }
+            None => None,
+        };
+
+        // Otherwise pick the safest neighbor
+        let next_node = next_node.or_else(|| {
+            let score = |node: Entity| {
+                let Ok((_, pos, concentrations)) = nav_nodes.get(node) else {
+                    return f32::NEG_INFINITY;
+                };
+                let player_threat = player_pos
+                    .map(|p| {
+                        (1. - p.distance(pos.translation().xy()) / config.player_radius).max(0.)
+                    })
+                    .unwrap_or_default();
+                config.crowd_weight * crowd.get(&node).copied().unwrap_or_default() as f32
+                    - config.danger_weight
+                        * concentrations.concentrations[PheromoneKind::DeadAnt as usize]
+                    - config.player_weight * player_threat
+            };
+            neighbors(current_node)
+                .into_iter()
+                .chain([current_node])
+                .max_by(|a, b| score(*a).total_cmp(&score(*b)))
+        });
+
+        if let (Some(next_pos), Some(current_pos)) =
+            (next_node.and_then(position), position(current_node))
+        {
+            let direction = (next_pos - current_pos).normalize_or_zero();
+            if direction != Vec3::ZERO {
+                queen_movement.direction = direction;
+            }
+        }
+    }
+}
@@ -2,16 +2,28 @@ use bevy::prelude::*;
 use bevy::reflect::Reflect;
 
 use crate::components::{
-    ants::{job::Job, movement::AntMovement, zombants::ZombAntQueen},
+    ants::{
+        caste::Caste,
+        dead_ants::DeadAnt,
+        job::Job,
+        lod::{lod_ticking, OffScreen},
+        movement::AntMovement,
+        zombants::ZombAntQueen,
+    },
+    cocoons::Cocoon,
+    midden::{Buried, Midden},
     nav_mesh::NavNode,
+    nursery::Nursery,
     object::Object,
-    pheromones::PheromoneKind,
+    pheromones::{concentrations::PheromoneConcentrations, PheromoneKind},
 };
 
 #[derive(Default, Debug, Clone, Copy, Reflect)]
 pub struct AntGoal {
     pub job: Job,
     pub holds: f32,
+    /// The food held comes from a [tainted](Object::tainted) object
+    pub tainted: bool,
 }
 
 impl AntGoal {
@@ -21,47 +33,62 @@ impl AntGoal {
         _object_id: Entity,
         object: &mut Object,
         direction: &mut Vec3,
+        capacity: f32,
     ) {
         self.job = Job::Storage;
 
         // None quantity represents unlimited resource
-        if let Some(quantity) = object.quantity {
-            if quantity > 1.0 {
-                object.quantity = Some(quantity - 1.0);
+        self.holds = match object.quantity {
+            Some(quantity) if quantity > capacity => {
+                object.quantity = Some(quantity - capacity);
                 // TODO concentration variation when resource quantity decreases
-            } else {
+                capacity
+            }
+            Some(quantity) => {
                 // Remove food when depleted
                 // commands.get_entity(object_id).unwrap().remove::<Object>();
+                object.quantity = Some(0.);
+                quantity
             }
-        }
-        self.holds = 1.0;
+            None => capacity,
+        };
+        self.tainted = object.tainted;
         *direction *= -1.;
     }
 
     pub fn reached_zombqueen(&mut self, direction: &mut Vec3, zombqueen: &mut ZombAntQueen) {
         if let Job::Offering = self.job {
             self.job = Job::Thief;
+            zombqueen.holds += self.holds;
             self.holds = 0.0;
-            zombqueen.holds += 2.;
             *direction *= -1.;
         }
     }
 
-    pub fn reached_storage_target(&mut self, object: &mut Object, direction: &mut Vec3) {
+    pub fn reached_storage_target(
+        &mut self,
+        object: &mut Object,
+        direction: &mut Vec3,
+        capacity: f32,
+    ) {
         match self.job {
             Job::Storage => {
                 self.job = Job::Food;
 
-                object.quantity = object.quantity.map(|q| q + 1.0).or(Some(1.0));
+                object.quantity = object.quantity.map(|q| q + self.holds).or(Some(self.holds));
+                object.tainted |= self.tainted;
                 self.holds = 0.;
+                self.tainted = false;
                 *direction *= -1.;
             }
             Job::Thief => {
                 self.job = Job::Offering;
 
                 if let Some(quantity) = object.quantity {
-                    object.quantity = Some((quantity - 2.).max(0.));
-                    self.holds = 2.;
+                    // Thieves grab more than they can carry properly
+                    let stolen = (2. * capacity).min(quantity);
+                    object.quantity = Some(quantity - stolen);
+                    self.holds = stolen;
                     *direction *= -1.;
                 }
             }
@@ -74,12 +101,25 @@ impl AntGoal {
 pub struct Metrics {
     pub food: f32,
     pub stolen: f32,
+    /// Sum of the [PheromoneKind::DeadAnt] and [PheromoneKind::Zombant] concentrations
+    pub threat: f32,
+    /// Number of cocoons in the nest
+    pub brood: usize,
+    /// Number of cocoons outside of the [Nursery]
+    pub brood_misplaced: usize,
+    /// Number of dead ants that can be carried to a [Midden]
+    pub corpses: usize,
 }
 
 pub fn update_metrics(
     mut metrics: ResMut<Metrics>,
     objects: Query<&Object>,
     zombqueen: Query<&ZombAntQueen>,
+    concentrations: Query<&PheromoneConcentrations>,
+    cocoons: Query<&Cocoon>,
+    nursery: Res<Nursery>,
+    dead_ants: Query<(), (With<DeadAnt>, Without<Buried>)>,
+    middens: Query<(), With<Midden>>,
 ) {
     metrics.food = 0.;
     for object in objects.iter() {
@@ -88,29 +128,52 @@ pub fn update_metrics(
         }
     }
 
-    if let Ok(zombqueen) = zombqueen.get_single() {
-        metrics.stolen = zombqueen.holds;
-    }
+    metrics.stolen = zombqueen.iter().map(|zombqueen| zombqueen.holds).sum();
+
+    metrics.threat = concentrations
+        .iter()
+        .map(|c| {
+            c.concentrations[PheromoneKind::DeadAnt as usize]
+                + c.concentrations[PheromoneKind::Zombant as usize]
+        })
+        .sum();
+    metrics.brood = cocoons.iter().count();
+    metrics.brood_misplaced = cocoons
+        .iter()
+        .filter(|cocoon| Some(cocoon.room) != nursery.room)
+        .count();
+    metrics.corpses = if middens.is_empty() {
+        0
+    } else {
+        dead_ants.iter().count()
+    };
 }
 
 pub fn update_ant_goal(
     //commands: &mut Commands,
-    mut ants: Query<&mut AntMovement, Without<ZombAntQueen>>,
+    mut ants: Query<(&mut AntMovement, &Caste, Option<&OffScreen>), Without<ZombAntQueen>>,
     mut objects: Query<(Entity, &mut Object, &GlobalTransform), With<NavNode>>,
     mut zombqueen: Query<(&mut ZombAntQueen, &AntMovement)>,
 ) {
-    for mut ant_movement in ants.iter_mut() {
+    for (mut ant_movement, caste, off_screen) in ants.iter_mut() {
+        if !lod_ticking(off_screen) {
+            continue;
+        }
         let current_object = objects.get_mut(ant_movement.current_node.0);
         if let Ok((object_id, mut object, _)) = current_object {
             if object.kind == ant_movement.goal.job.follows() {
-                ant_movement.reached_object(/*commands,*/ object_id, &mut object)
+                ant_movement.reached_object(
+                    /*commands,*/ object_id,
+                    &mut object,
+                    caste.capacity(),
+                )
             }
         }
 
-        if let Ok((mut zombqueen, zombqueen_movement)) = zombqueen.get_single_mut() {
-            if zombqueen_movement.current_node.0 == ant_movement.current_node.0 {
-                ant_movement.reached_zombqueen(&mut zombqueen);
-            }
+        if let Some((mut zombqueen, _)) = zombqueen.iter_mut().find(|(_, zombqueen_movement)| {
+            zombqueen_movement.current_node.0 == ant_movement.current_node.0
+        }) {
+            ant_movement.reached_zombqueen(&mut zombqueen);
         }
     }
 }
@@ -0,0 +1,141 @@
+use bevy::{prelude::*, utils::HashSet};
+use bevy_rapier2d::prelude::RigidBody;
+use rand::{thread_rng, Rng};
+
+use crate::{
+    components::{
+        ants::{
+            alarm::Panic,
+            dead_ants::Dying,
+            job::Job,
+            life::AntLife,
+            live_ants::LiveAnt,
+            lod::{lod_delta, OffScreen},
+            movement::AntMovement,
+            zombants::{ZombAnt, ZombAntQueen},
+        },
+        cocoons::Cocoon,
+        nursery::{drop_carried, Carried},
+        object::Object,
+    },
+    resources::nav_mesh_lut::NavMeshLUT,
+};
+
+/// Live ant incubating the zombie infection
+#[derive(Debug, Default, Clone, Copy, Component, Reflect)]
+pub struct Infection {
+    /// Seconds since the infection, the ant turns after [InfectionConfig::incubation_time]
+    pub incubation: f32,
+}
+
+#[derive(Debug, Clone, Resource, Reflect)]
+pub struct InfectionConfig {
+    /// Probability per second to be infected when sharing a node with a zombant
+    pub contact_rate: f32,
+    /// Probability per second to be infected when carrying tainted food
+    pub tainted_food_rate: f32,
+    pub incubation_time: f32,
+}
+
+impl Default for InfectionConfig {
+    fn default() -> Self {
+        Self {
+            contact_rate: 0.05,
+            tainted_food_rate: 0.1,
+            incubation_time: 30.,
+        }
+    }
+}
+
+/// The Zomb'Ant Queen taints the food on her node
+pub fn update_tainted_objects(
+    queens: Query<&AntMovement, With<ZombAntQueen>>,
+    mut objects: Query<&mut Object>,
+) {
+    for queen_movement in queens.iter() {
+        if let Ok(mut object) = objects.get_mut(queen_movement.current_node.0) {
+            object.tainted = true;
+        }
+    }
+}
+
+/// Infect live ants and cocoons close to zombants, and turn ants at the end of their incubation
+pub fn update_infection(
+    mut commands: Commands,
+    mut ants: ParamSet<(
+        Query<&AntMovement, Or<(With<ZombAnt>, With<ZombAntQueen>)>>,
+        Query<
+            (
+                Entity,
+                &mut AntMovement,
+                &Transform,
+                Option<&mut Infection>,
+                Option<&Panic>,
+                Option<&OffScreen>,
+            ),
+            (With<LiveAnt>, Without<Dying>),
+        >,
+    )>,
+    mut cocoons: Query<(&mut Cocoon, &GlobalTransform)>,
+    mut carried: Query<(Entity, &Carried, &mut Transform, Option<&RigidBody>), Without<LiveAnt>>,
+    nav_mesh_lut: Res<NavMeshLUT>,
+    config: Res<InfectionConfig>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    let mut rng = thread_rng();
+    let mut roll = |rate: f32, dt: f32| rng.gen::<f32>() < rate * dt;
+    let zombant_nodes: HashSet<Entity> = ants.p0().iter().map(|m| m.current_node.0).collect();
+
+    for (ant, mut ant_movement, ant_transform, infection, panic, off_screen) in ants.p1().iter_mut()
+    {
+        let Some(dt) = lod_delta(off_screen, dt) else {
+            continue;
+        };
+        match infection {
+            Some(mut infection) => {
+                infection.incubation += dt;
+                if infection.incubation >= config.incubation_time {
+                    ant_movement.goal.job = Job::Thief;
+                    if let Some(panic) = panic {
+                        ant_movement.speed = panic.speed;
+                    }
+                    commands
+                        .entity(ant)
+                        .remove::<(LiveAnt, AntLife, Infection, Panic)>()
+                        .insert(ZombAnt {});
+                    // Zombants do not relocate cocoons nor bury dead ants
+                    for (entity, _, mut transform, rigid_body) in carried
+                        .iter_mut()
+                        .filter(|(_, carried, _, _)| carried.carrier == ant)
+                    {
+                        transform.translation.x = ant_transform.translation.x;
+                        transform.translation.y = ant_transform.translation.y;
+                        drop_carried(&mut commands, entity, rigid_body);
+                    }
+                }
+            }
+            None => {
+                let goal = ant_movement.goal;
+                if (zombant_nodes.contains(&ant_movement.current_node.0)
+                    && roll(config.contact_rate, dt))
+                    || (goal.tainted && goal.holds > 0. && roll(config.tainted_food_rate, dt))
+                {
+                    commands.entity(ant).insert(Infection::default());
+                }
+            }
+        }
+    }
+
+    for (mut cocoon, transform) in cocoons.iter_mut() {
+        if cocoon.infected {
+            continue;
+        }
+        let on_zombant_node = nav_mesh_lut
+            .get_tile_entity(transform.translation().xy())
+            .is_some_and(|(node, _)| zombant_nodes.contains(&node));
+        if on_zombant_node && roll(config.contact_rate, dt) {
+            cocoon.infected = true;
+        }
+    }
+}
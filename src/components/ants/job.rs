@@ -1,8 +1,10 @@
+use std::str::FromStr;
+
 use bevy::reflect::Reflect;
 
 use crate::components::pheromones::PheromoneKind;
 
-#[derive(Debug, Default, Clone, Copy, Reflect)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
 pub enum Job {
     #[default]
     Wander,
@@ -10,6 +12,16 @@ pub enum Job {
     Storage,
     Thief,
     Offering,
+    /// Looking for cocoons to move to the nursery
+    Nurse,
+    /// Carrying a cocoon to the nursery
+    Relocate,
+    /// Hunting zombants, see [update_soldiers](super::soldiers::update_soldiers)
+    Patrol,
+    /// Looking for dead ants to carry to the midden
+    Undertaker,
+    /// Carrying a dead ant to the midden
+    Bury,
 }
 
 impl Job {
@@ -20,6 +32,32 @@ impl Job {
             Job::Storage => PheromoneKind::Storage,
             Job::Thief => PheromoneKind::Storage,
             Job::Offering => PheromoneKind::Zombqueen,
+            Job::Nurse => PheromoneKind::Brood,
+            Job::Relocate => PheromoneKind::Nursery,
+            Job::Patrol => PheromoneKind::Zombant,
+            Job::Undertaker => PheromoneKind::DeadAnt,
+            Job::Bury => PheromoneKind::Midden,
+        }
+    }
+
+    /// Pheromone followed when there is no gradient of [Job::follows]
+    pub fn fallback_follows(&self) -> Option<PheromoneKind> {
+        match self {
+            Job::Nurse => Some(PheromoneKind::Queen),
+            Job::Patrol => Some(PheromoneKind::DeadAnt),
+            _ => None,
+        }
+    }
+
+    /// Task the job belongs to, jobs alternating during a round trip share the same task
+    pub fn task(&self) -> Job {
+        match self {
+            Job::Wander => Job::Wander,
+            Job::Food | Job::Storage => Job::Food,
+            Job::Thief | Job::Offering => Job::Thief,
+            Job::Nurse | Job::Relocate => Job::Nurse,
+            Job::Patrol => Job::Patrol,
+            Job::Undertaker | Job::Bury => Job::Undertaker,
         }
     }
 
@@ -30,6 +68,31 @@ impl Job {
             Job::Storage => Job::Food,
             Job::Thief => Job::Offering,
             Job::Offering => Job::Thief,
+            Job::Nurse => Job::Relocate,
+            Job::Relocate => Job::Nurse,
+            Job::Patrol => Job::Patrol,
+            Job::Undertaker => Job::Bury,
+            Job::Bury => Job::Undertaker,
+        }
+    }
+}
+
+impl FromStr for Job {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "wander" => Ok(Job::Wander),
+            "food" => Ok(Job::Food),
+            "storage" => Ok(Job::Storage),
+            "thief" => Ok(Job::Thief),
+            "offering" => Ok(Job::Offering),
+            "nurse" => Ok(Job::Nurse),
+            "relocate" => Ok(Job::Relocate),
+            "patrol" => Ok(Job::Patrol),
+            "undertaker" => Ok(Job::Undertaker),
+            "bury" => Ok(Job::Bury),
+            _ => Err(format!("unknown job `{s}`")),
         }
     }
 }
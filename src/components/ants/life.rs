@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+use rand::{rngs::ThreadRng, Rng};
+
+use crate::components::{
+    ants::{
+        dead_ants::kill_ant,
+        live_ants::LiveAnt,
+        lod::{lod_delta, OffScreen},
+    },
+    object::{take_from_storage, Object},
+};
+
+/// Age and energy of a live ant
+#[derive(Debug, Clone, Copy, Component, Reflect)]
+pub struct AntLife {
+    /// Age in seconds
+    pub age: f32,
+    /// Age at which the ant dies
+    pub lifespan: f32,
+    /// Between 0 (starving) and 1 (fed)
+    pub energy: f32,
+}
+
+impl AntLife {
+    pub fn new(config: &LifeConfig, rng: &mut ThreadRng) -> Self {
+        Self {
+            // Ants start at different ages so they do not all die at the same time
+            age: rng.gen_range(0.0..config.lifespan / 2.),
+            lifespan: config.lifespan * rng.gen_range(0.8..1.2),
+            energy: 1.,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Resource, Reflect)]
+pub struct LifeConfig {
+    /// Average lifespan in seconds
+    pub lifespan: f32,
+    /// Energy spent per second
+    pub energy_consumption: f32,
+    /// Energy under which the ant eats
+    pub hunger: f32,
+    /// Food taken from the storage to fill the energy of an ant
+    pub meal: f32,
+}
+
+impl Default for LifeConfig {
+    fn default() -> Self {
+        Self {
+            lifespan: 600.,
+            energy_consumption: 1. / 120.,
+            hunger: 0.5,
+            meal: 0.1,
+        }
+    }
+}
+
+/// Age ants and feed them from the storage, killing them of old age or starvation
+pub fn update_ant_life(
+    mut commands: Commands,
+    mut ants: Query<(Entity, &mut AntLife, Option<&OffScreen>), With<LiveAnt>>,
+    mut objects: Query<&mut Object>,
+    config: Res<LifeConfig>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    for (ant, mut life, off_screen) in ants.iter_mut() {
+        let Some(dt) = lod_delta(off_screen, dt) else {
+            continue;
+        };
+        life.age += dt;
+        life.energy -= config.energy_consumption * dt;
+        if life.energy < config.hunger {
+            let needed = config.meal * (1. - life.energy);
+            let eaten = take_from_storage(objects.iter_mut(), needed);
+            if needed > 0. {
+                life.energy += (1. - life.energy) * eaten / needed;
+            }
+        }
+        if life.age > life.lifespan || life.energy <= 0. {
+            kill_ant(&mut commands, ant);
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::Rng;
+
+use crate::{
+    components::{
+        ants::{
+            movement::{
+                falling::Falling,
+                position::{ant_material_for, ant_transform_on_nav_node},
+                steering::project_on_surface,
+                AntMovement,
+            },
+            zombants::ZombAntQueen,
+            AntStyle,
+        },
+        nav_mesh::NavNode,
+        pheromones::concentrations::PheromoneConcentrations,
+    },
+    render::{render_ant::AntMaterial, MainCamera2d},
+    TILE_SIZE,
+};
+
+/// Ant outside of the view of the [MainCamera2d], it hops from node to node instead of being
+/// fully simulated
+///
+/// Its collider is disabled, and the per-ant systems only handle it on its ticks, see [lod_delta].
+#[derive(Debug, Default, Clone, Copy, Component, Reflect)]
+pub struct OffScreen {
+    /// Seconds since the last tick
+    elapsed: f32,
+    /// Distance walked towards the next node
+    progress: f32,
+    /// Seconds covered by the tick happening this frame, if any
+    tick: Option<f32>,
+}
+
+/// Seconds to simulate this frame for an ant, `None` for an off-screen ant between two ticks
+pub fn lod_delta(off_screen: Option<&OffScreen>, dt: f32) -> Option<f32> {
+    match off_screen {
+        Some(off_screen) => off_screen.tick,
+        None => Some(dt),
+    }
+}
+
+/// Number of frames covered this frame for an ant, to scale its per-frame deposits, `None` for an
+/// off-screen ant between two ticks
+pub fn lod_frames(off_screen: Option<&OffScreen>, dt: f32) -> Option<f32> {
+    match off_screen {
+        Some(off_screen) => off_screen
+            .tick
+            .map(|tick| if dt > 0. { tick / dt } else { 1. }),
+        None => Some(1.),
+    }
+}
+
+/// The ant is simulated this frame, see [lod_delta]
+pub fn lod_ticking(off_screen: Option<&OffScreen>) -> bool {
+    off_screen.map_or(true, |off_screen| off_screen.tick.is_some())
+}
+
+/// Level of detail of the simulation of the ants
+#[derive(Debug, Clone, Resource, Reflect)]
+pub struct LodConfig {
+    pub enabled: bool,
+    /// Distance around the view under which ants are still fully simulated, so they do not change
+    /// behaviour in sight
+    pub margin: f32,
+    /// Seconds between two ticks of the off-screen ants
+    pub tick_period: f32,
+    /// Maximum number of nodes an off-screen ant goes through per tick
+    pub max_hops: u32,
+}
+
+impl Default for LodConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            margin: 4. * TILE_SIZE,
+            tick_period: 0.25,
+            max_hops: 4,
+        }
+    }
+}
+
+/// Switch the ants leaving the view to the off-screen model, and back to the full simulation when
+/// they come close to it
+///
+/// The Zomb'Ant Queens and the falling ants are always fully simulated.
+pub fn update_ant_lod(
+    mut commands: Commands,
+    ants: Query<
+        (Entity, &GlobalTransform, Has<OffScreen>, Has<Falling>),
+        (With<AntMovement>, Without<ZombAntQueen>),
+    >,
+    cameras: Query<(&GlobalTransform, &OrthographicProjection), With<MainCamera2d>>,
+    config: Res<LodConfig>,
+) {
+    let view = match cameras.get_single() {
+        Ok((camera_transform, projection)) if config.enabled => {
+            let center = camera_transform.translation().xy();
+            Some(Rect::from_corners(
+                center + projection.area.min - config.margin,
+                center + projection.area.max + config.margin,
+            ))
+        }
+        _ => None,
+    };
+    let mut rng = rand::thread_rng();
+    for (ant, ant_transform, off_screen, falling) in ants.iter() {
+        let visible =
+            falling || view.map_or(true, |view| view.contains(ant_transform.translation().xy()));
+        if visible && off_screen {
+            commands
+                .entity(ant)
+                .remove::<(OffScreen, ColliderDisabled)>();
+        } else if !visible && !off_screen {
+            // Spread the ticks of the ants over the period
+            commands.entity(ant).insert((
+                OffScreen {
+                    elapsed: rng.gen::<f32>() * config.tick_period,
+                    ..default()
+                },
+                ColliderDisabled,
+            ));
+        }
+    }
+}
+
+/// Advance the clock of the off-screen ants, it must run before all the systems using
+/// [lod_delta]
+pub fn tick_offscreen_ants(
+    mut ants: Query<&mut OffScreen>,
+    config: Res<LodConfig>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    for mut off_screen in ants.iter_mut() {
+        off_screen.elapsed += dt;
+        if off_screen.elapsed < config.tick_period {
+            off_screen.tick = None;
+        } else {
+            off_screen.tick = Some(off_screen.elapsed);
+            off_screen.elapsed = 0.;
+        }
+    }
+}
+
+/// Move the off-screen ants along the nav mesh at a reduced tick rate
+///
+/// Ants go to the neighbor that smells the most of the pheromone they follow, preferring to keep
+/// their direction. They always stand on a node, so the full simulation resumes seamlessly. Goals
+/// are only handled on the node where a tick ends, by
+/// [update_ant_goal](crate::components::ants::goal::update_ant_goal).
+pub fn update_offscreen_ants(
+    mut ants: Query<
+        (
+            &mut AntMovement,
+            &mut OffScreen,
+            &mut Transform,
+            &mut Handle<AntMaterial>,
+            &AntStyle,
+            &Parent,
+        ),
+        Without<Falling>,
+    >,
+    holders: Query<&GlobalTransform, Without<NavNode>>,
+    nav_nodes: Query<(&NavNode, &GlobalTransform, &PheromoneConcentrations)>,
+    config: Res<LodConfig>,
+) {
+    let mut rng = rand::thread_rng();
+    for (
+        mut ant_movement,
+        mut off_screen,
+        mut ant_transform,
+        mut ant_material,
+        ant_style,
+        parent,
+    ) in ants.iter_mut()
+    {
+        let Some(tick) = off_screen.tick else {
+            continue;
+        };
+        off_screen.progress += ant_movement.speed * tick;
+        let Ok(holder_transform) = holders.get(parent.get()) else {
+            continue;
+        };
+
+        let job = ant_movement.goal.job;
+        let smell = |concentrations: &PheromoneConcentrations| {
+            concentrations.concentrations[job.follows() as usize]
+                + job
+                    .fallback_follows()
+                    .map(|kind| concentrations.concentrations[kind as usize])
+                    .unwrap_or_default()
+        };
+        let mut hops = 0;
+        while off_screen.progress >= TILE_SIZE && hops < config.max_hops {
+            off_screen.progress -= TILE_SIZE;
+            hops += 1;
+            let Ok((nav_node, node_transform, _)) = nav_nodes.get(ant_movement.current_node.0)
+            else {
+                break;
+            };
+            let node_pos = node_transform.translation();
+            let direction = ant_movement.direction.normalize_or_zero();
+            let neighbors: Vec<_> = nav_node
+                .neighbors()
+                .into_iter()
+                .filter_map(|neighbor| {
+                    let (_, transform, concentrations) = nav_nodes.get(neighbor).ok()?;
+                    let offset = (transform.translation() - node_pos).normalize_or_zero();
+                    Some((neighbor, offset, smell(concentrations)))
+                })
+                .collect();
+            // Relative to the neighbors, so the smell weighs as much as the direction whatever the
+            // concentrations
+            let max_smell = neighbors
+                .iter()
+                .map(|(_, _, smell)| *smell)
+                .fold(0., f32::max);
+            let next = neighbors
+                .into_iter()
+                .map(|(neighbor, offset, smell)| {
+                    let smell = if max_smell > 0. {
+                        smell / max_smell
+                    } else {
+                        0.
+                    };
+                    let score = smell + offset.dot(direction) + rng.gen::<f32>();
+                    (neighbor, offset, score)
+                })
+                .max_by(|a, b| a.2.total_cmp(&b.2));
+            let Some((neighbor, offset, _)) = next else {
+                break;
+            };
+            let Ok((neighbor_node, neighbor_transform, _)) = nav_nodes.get(neighbor) else {
+                break;
+            };
+
+            let (transform, position_kind) = ant_transform_on_nav_node(
+                neighbor_node,
+                neighbor_transform,
+                holder_transform,
+                ant_style,
+            );
+            ant_transform.translation = transform.translation;
+            ant_movement.current_node = (neighbor, *neighbor_transform);
+            if std::mem::discriminant(&position_kind)
+                != std::mem::discriminant(&ant_movement.position_kind)
+            {
+                *ant_material = ant_material_for(position_kind);
+            }
+            ant_movement.position_kind = position_kind;
+            let heading = project_on_surface(offset, position_kind)
+                .try_normalize()
+                .unwrap_or(direction);
+            ant_movement.heading = heading;
+            ant_movement.direction = heading;
+            ant_movement.angular_velocity = 0.;
+        }
+        // Do not accumulate distance when the ant is stuck
+        off_screen.progress = off_screen.progress.min(TILE_SIZE);
+    }
+}
@@ -7,13 +7,25 @@ use crate::components::{
 };
 
 pub mod direction;
+pub mod falling;
+pub mod nav_graph;
 pub mod position;
+pub mod steering;
 
 #[derive(Debug, Clone, Copy, Component, Reflect)]
 pub struct AntMovement {
     pub position_kind: AntPositionKind,
     pub speed: f32,
+    /// Desired direction, set by the behaviours of the ant
     pub direction: Vec3,
+    /// Direction the ant is actually moving towards, turned towards `direction` by
+    /// [update_ant_steering](steering::update_ant_steering)
+    pub heading: Vec3,
+    /// Current turning speed of the heading, in radians per second
+    pub angular_velocity: f32,
+    /// Offset added to `direction` to avoid the other ants, see
+    /// [update_crowd_avoidance](crate::components::ants::crowd::update_crowd_avoidance)
+    pub avoidance: Vec3,
     pub current_node: (Entity, GlobalTransform), // FIXME: use relative transforms
     pub goal: AntGoal,
     pub last_direction_update: f32,
@@ -25,15 +37,19 @@ impl AntMovement {
         /*commands: &mut Commands, FIXME breaks trait for `.chain` in lib */
         object_id: Entity,
         object: &mut Object,
+        capacity: f32,
     ) {
         match object.kind {
-            PheromoneKind::Storage => self
-                .goal
-                .reached_storage_target(object, &mut self.direction),
-            PheromoneKind::Food => {
+            PheromoneKind::Storage => {
                 self.goal
-                    .reached_food_target(object_id, object, &mut self.direction)
-            } /*commands,*/
+                    .reached_storage_target(object, &mut self.direction, capacity)
+            }
+            PheromoneKind::Food => self.goal.reached_food_target(
+                /*commands,*/ object_id,
+                object,
+                &mut self.direction,
+                capacity,
+            ),
             _ => (),
         }
     }
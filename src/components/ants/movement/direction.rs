@@ -1,33 +1,178 @@
-use std::f32::consts::PI;
+use std::{collections::VecDeque, f32::consts::PI};
 
-use crate::components::{
-    ants::movement::{position::AntPositionKind, AntMovement},
-    pheromones::gradients::PheromoneGradients,
+use crate::{
+    components::{
+        ants::{
+            lod::OffScreen,
+            movement::{position::AntPositionKind, AntMovement},
+            zombants::ZombAntQueen,
+        },
+        nav_mesh::NavNode,
+        pheromones::{
+            concentrations::PheromoneConcentrations, gradients::PheromoneGradients, PheromoneKind,
+        },
+    },
+    TILE_SIZE,
 };
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashSet};
 
 use rand::Rng;
 
-/// Calculate desired direction of ants according to the gradient of the current node
+/// How ants smell the pheromones ahead of them
+#[derive(Debug, Clone, Resource, Reflect)]
+pub struct SensingConfig {
+    /// Number of nodes sampled ahead of the ant, along the nav mesh
+    pub depth: usize,
+    /// Half angle of the cone in which nodes are sampled, in radians
+    pub cone_angle: f32,
+    /// Rate at which the heading blends towards the best sample, per second
+    pub blend_rate: f32,
+    /// Maximum deviation from the best sample, in radians
+    pub jitter: f32,
+}
+
+impl Default for SensingConfig {
+    fn default() -> Self {
+        Self {
+            depth: 3,
+            cone_angle: PI / 3.,
+            blend_rate: 4.,
+            jitter: PI / 12.,
+        }
+    }
+}
+
+/// Direction of the node with the highest concentration of `kind` in the cone ahead of the ant,
+/// if it is higher than on the current node
+fn sense_ahead(
+    start: Entity,
+    heading: Vec3,
+    kind: PheromoneKind,
+    nodes: &Query<(
+        &NavNode,
+        &GlobalTransform,
+        &PheromoneConcentrations,
+        &PheromoneGradients,
+    )>,
+    config: &SensingConfig,
+) -> Option<Vec3> {
+    let (_, start_pos, start_concentrations, _) = nodes.get(start).ok()?;
+    let start_pos = start_pos.translation();
+    let heading = heading.normalize_or_zero();
+    let cone_cos = config.cone_angle.cos();
+    let mut best = (start_concentrations.concentrations[kind as usize], None);
+    let mut visited = HashSet::new();
+    visited.insert(start);
+    let mut queue = VecDeque::from([(start, 0)]);
+    while let Some((node, depth)) = queue.pop_front() {
+        if depth >= config.depth {
+            continue;
+        }
+        let Ok((nav_node, _, _, _)) = nodes.get(node) else {
+            continue;
+        };
+        for neighbor in nav_node.neighbors() {
+            if !visited.insert(neighbor) {
+                continue;
+            }
+            let Ok((_, pos, concentrations, _)) = nodes.get(neighbor) else {
+                continue;
+            };
+            let offset = pos.translation() - start_pos;
+            // Nodes on the sides of the ant are in the cone if close enough
+            if offset.normalize_or_zero().dot(heading) < cone_cos && offset.length() > TILE_SIZE {
+                continue;
+            }
+            let concentration = concentrations.concentrations[kind as usize];
+            if concentration > best.0 {
+                best = (concentration, Some(offset));
+            }
+            queue.push_back((neighbor, depth + 1));
+        }
+    }
+    best.1.map(|offset| offset.normalize_or_zero())
+}
+
+/// Axis around which the ant can turn while staying on its surface
+pub fn turning_axis(position_kind: AntPositionKind) -> Vec3 {
+    match position_kind {
+        AntPositionKind::Background => Vec3::Z,
+        AntPositionKind::VerticalWall { .. } => Vec3::X,
+        AntPositionKind::HorizontalWall { .. } => Vec3::Y,
+    }
+}
+
+/// Calculate desired direction of ants according to the pheromones sensed ahead of them, or the
+/// gradient of the current node
+///
+/// The Zomb'Ant Queen is steered by [update_queen_evasion](crate::components::ants::evasion::update_queen_evasion)
 pub fn update_ant_direction(
-    mut ants: Query<&mut AntMovement>,
-    gradients: Query<&PheromoneGradients>,
+    mut ants: Query<&mut AntMovement, (Without<ZombAntQueen>, Without<OffScreen>)>,
+    nodes: Query<(
+        &NavNode,
+        &GlobalTransform,
+        &PheromoneConcentrations,
+        &PheromoneGradients,
+    )>,
+    config: Res<SensingConfig>,
     time: Res<Time>,
 ) {
     let mut rng = rand::thread_rng();
+    let dt = time.delta_seconds();
 
     for mut ant_movement in ants.iter_mut() {
-        let closest_gradient = gradients.get(ant_movement.current_node.0).unwrap();
+        let Ok((_, _, _, closest_gradient)) = nodes.get(ant_movement.current_node.0) else {
+            continue;
+        };
 
         let elapsed = time.elapsed_seconds();
+        let job = ant_movement.goal.job;
+
+        // Blend the heading towards the best node ahead, the cone follows where the ant is
+        // actually going
+        let sensed = [Some(job.follows()), job.fallback_follows()]
+            .into_iter()
+            .flatten()
+            .find_map(|kind| {
+                sense_ahead(
+                    ant_movement.current_node.0,
+                    ant_movement.heading,
+                    kind,
+                    &nodes,
+                    &config,
+                )
+            });
+        if let Some(target) = sensed {
+            let jitter = Quat::from_axis_angle(
+                turning_axis(ant_movement.position_kind),
+                rng.gen_range(-config.jitter..=config.jitter),
+            );
+            let target = jitter * target;
+            let blended = ant_movement
+                .direction
+                .lerp(target, (config.blend_rate * dt).min(1.))
+                .normalize_or_zero();
+            ant_movement.direction = if blended == Vec3::ZERO {
+                target
+            } else {
+                blended
+            };
+            ant_movement.last_direction_update = elapsed;
+            continue;
+        }
 
         let random = rng.gen_range(0.0..1.0);
         // the gradient for the pheromon the ant follows is not null: follow its direction for at least a second
-        let goal_gradient = closest_gradient.gradients[ant_movement.goal.job.follows() as usize];
+        let mut goal_gradient = closest_gradient.gradients[job.follows() as usize];
+        if goal_gradient == Vec3::ZERO {
+            if let Some(fallback) = job.fallback_follows() {
+                goal_gradient = closest_gradient.gradients[fallback as usize];
+            }
+        }
         if goal_gradient != Vec3::ZERO
             && elapsed - ant_movement.last_direction_update > random + 0.5
         {
-            // TODO randomize a bit the direction
+            // Nothing better ahead, the ant has to turn back
             ant_movement.direction = goal_gradient;
             ant_movement.last_direction_update = elapsed;
         } else {
@@ -0,0 +1,147 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{
+        ants::{
+            movement::{
+                nav_graph::snap_to_node,
+                position::{ant_material_for, AntPositionKind},
+                steering::carry_heading_over,
+                AntMovement,
+            },
+            AntStyle,
+        },
+        nav_mesh::NavNode,
+    },
+    render::render_ant::AntMaterial,
+    resources::nav_mesh_lut::NavMeshLUT,
+    TILE_SIZE,
+};
+
+/// Ant that walked off a ledge of the surface, it keeps its last [AntMovement::current_node]
+/// until it lands
+#[derive(Debug, Default, Clone, Copy, Component, Reflect)]
+pub struct Falling {
+    /// Downward speed
+    pub velocity: f32,
+}
+
+#[derive(Debug, Clone, Resource, Reflect)]
+pub struct FallConfig {
+    /// Downward acceleration, in pixels per second squared
+    pub gravity: f32,
+    pub max_velocity: f32,
+}
+
+impl Default for FallConfig {
+    fn default() -> Self {
+        Self {
+            gravity: 20. * TILE_SIZE,
+            max_velocity: 12. * TILE_SIZE,
+        }
+    }
+}
+
+/// Turn an ant on a surface edge back from its front side, which has no background behind
+pub fn stay_on_surface(ant_movement: &mut AntMovement) {
+    ant_movement.direction.z = ant_movement.direction.z.abs();
+    ant_movement.heading.z = ant_movement.heading.z.abs();
+}
+
+/// Make ants fall under gravity until they land on a floor, or catch the background once they
+/// fall into the nest
+pub fn update_falling_ants(
+    mut commands: Commands,
+    mut ants: Query<(
+        Entity,
+        &mut AntMovement,
+        &mut Falling,
+        &mut Transform,
+        &mut Handle<AntMaterial>,
+        &AntStyle,
+        &Parent,
+    )>,
+    holders: Query<&GlobalTransform, Without<NavNode>>,
+    nav_nodes: Query<(&NavNode, &GlobalTransform)>,
+    nav_mesh_lut: Res<NavMeshLUT>,
+    config: Res<FallConfig>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    let map_size = Vec2::new(
+        (nav_mesh_lut.tile_width * nav_mesh_lut.grid_width) as f32,
+        (nav_mesh_lut.tile_height * nav_mesh_lut.grid_height) as f32,
+    );
+    for (
+        ant,
+        mut ant_movement,
+        mut falling,
+        mut ant_transform,
+        mut ant_material,
+        ant_style,
+        parent,
+    ) in ants.iter_mut()
+    {
+        falling.velocity = (falling.velocity + config.gravity * dt).min(config.max_velocity);
+        // The entities holder is only translated
+        let holder_xy = holders
+            .get(parent.get())
+            .map(|holder| holder.translation().xy())
+            .unwrap_or_default();
+        // Clamp to avoid going through floors when lagging
+        let delta = Vec2::new(
+            ant_movement.heading.x * ant_movement.speed,
+            -falling.velocity,
+        ) * dt;
+        let delta = delta.clamp_length_max(TILE_SIZE / 4.);
+        let current_xy = ant_transform.translation.xy() + holder_xy;
+        let mut xy = (current_xy + delta).clamp(Vec2::ZERO, map_size - Vec2::ONE);
+        // Slide down along the ground instead of going through it
+        if nav_mesh_lut
+            .get_tile_index(Vec2::new(xy.x, current_xy.y))
+            .is_some_and(|index| nav_mesh_lut.grid_is_ground[index])
+        {
+            xy.x = current_xy.x;
+        }
+        let z = ant_transform.translation.z;
+
+        // Land on the floor of the tile, or catch the background in the nest
+        let reach = ant_style.size().y / 2. - ant_style.wall_clipping();
+        let landing = nav_mesh_lut.get_tile_index(xy).and_then(|index| {
+            let floor = nav_mesh_lut.get_tile_edges(index).down.filter(|floor| {
+                nav_nodes
+                    .get(*floor)
+                    .is_ok_and(|(_, transform)| xy.y <= transform.translation().y + reach)
+            });
+            // Only the tiles of the nest have a background
+            let background =
+                nav_mesh_lut.grid_is_empty[index].then(|| nav_mesh_lut.grid_entity[index]);
+            let node = floor.or(background)?;
+            nav_nodes
+                .get(node)
+                .ok()
+                .map(|(nav_node, transform)| (node, nav_node, transform))
+        });
+        let Some((node, nav_node, node_transform)) = landing else {
+            ant_transform.translation = (xy - holder_xy).extend(z);
+            continue;
+        };
+
+        let (xy, z, position_kind) = snap_to_node(
+            xy,
+            z,
+            nav_node,
+            node_transform.translation().xy(),
+            ant_style,
+        );
+        ant_transform.translation = (xy - holder_xy).extend(z);
+        ant_movement.current_node = (node, *node_transform);
+        ant_movement.position_kind = position_kind;
+        if matches!(position_kind, AntPositionKind::Background) {
+            // Keep going down into the nest
+            carry_heading_over(&mut ant_movement, Vec3::NEG_Y);
+        }
+        *ant_material = ant_material_for(position_kind);
+        commands.entity(ant).remove::<Falling>();
+    }
+}
@@ -0,0 +1,433 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    components::{
+        ants::{
+            lod::OffScreen,
+            movement::{
+                falling::{stay_on_surface, Falling},
+                position::{ant_material_for, AntPositionKind},
+                steering::carry_heading_over,
+                AntMovement,
+            },
+            AntStyle,
+        },
+        nav_mesh::{EdgeNeighbor, EdgeNeighborKind, NavNode},
+    },
+    render::render_ant::AntMaterial,
+    COLLISION_GROUP_WALLS, TILE_SIZE, WALL_Z_FACTOR,
+};
+
+/// How ants find the walls they walk on
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Resource, Reflect)]
+pub enum LocomotionMode {
+    /// Walls are detected by the collisions of the ants with the edges of the nav mesh, see
+    /// [update_ant_position_kinds](super::position::update_ant_position_kinds)
+    #[default]
+    Physics,
+    /// Ants move along the links between [NavNode]s and only collide with gameplay sensors, which
+    /// is much cheaper for large colonies
+    NavGraph,
+}
+
+/// Only ants using [LocomotionMode::Physics] collide with walls
+pub fn update_ant_collision_groups(
+    mode: Res<LocomotionMode>,
+    mut ants: Query<(Ref<AntMovement>, &mut CollisionGroups)>,
+) {
+    for (ant_movement, mut collision_groups) in ants.iter_mut() {
+        if !mode.is_changed() && !ant_movement.is_added() {
+            continue;
+        }
+        collision_groups
+            .filters
+            .set(COLLISION_GROUP_WALLS, *mode == LocomotionMode::Physics);
+    }
+}
+
+/// Position on `nav_node` closest to `xy` and `z`, for an ant with `ant_style`
+pub fn snap_to_node(
+    xy: Vec2,
+    z: f32,
+    nav_node: &NavNode,
+    node_xy: Vec2,
+    ant_style: &AntStyle,
+) -> (Vec2, f32, AntPositionKind) {
+    let half_tile = TILE_SIZE / 2.;
+    let reach = ant_style.size() / 2. - ant_style.wall_clipping();
+    let z_on_wall = z.clamp(ant_style.wall_clipping(), TILE_SIZE * WALL_Z_FACTOR);
+    match *nav_node {
+        NavNode::Background { .. } => (xy, 0., AntPositionKind::Background),
+        NavNode::VerticalEdge { is_left_side, .. } => (
+            Vec2::new(
+                node_xy.x + reach.x * if is_left_side { 1. } else { -1. },
+                xy.y.clamp(node_xy.y - half_tile, node_xy.y + half_tile),
+            ),
+            z_on_wall,
+            AntPositionKind::VerticalWall { is_left_side },
+        ),
+        NavNode::HorizontalEdge { is_up_side, .. } => (
+            Vec2::new(
+                xy.x.clamp(node_xy.x - half_tile, node_xy.x + half_tile),
+                node_xy.y + reach.y * if is_up_side { -1. } else { 1. },
+            ),
+            z_on_wall,
+            AntPositionKind::HorizontalWall { is_up_side },
+        ),
+    }
+}
+
+/// Move the ant onto `node`, carrying its heading over with `push` if it changes surface
+fn enter_node(
+    ant_movement: &mut AntMovement,
+    xy: &mut Vec2,
+    z: &mut f32,
+    node: Entity,
+    nav_nodes: &Query<(&NavNode, &GlobalTransform)>,
+    ant_style: &AntStyle,
+    push: Vec3,
+) {
+    let Ok((nav_node, node_transform)) = nav_nodes.get(node) else {
+        return;
+    };
+    let (new_xy, new_z, position_kind) = snap_to_node(
+        *xy,
+        *z,
+        nav_node,
+        node_transform.translation().xy(),
+        ant_style,
+    );
+    *xy = new_xy;
+    *z = new_z;
+    ant_movement.current_node = (node, *node_transform);
+    let changed_surface = std::mem::discriminant(&position_kind)
+        != std::mem::discriminant(&ant_movement.position_kind);
+    ant_movement.position_kind = position_kind;
+    if changed_surface {
+        carry_heading_over(ant_movement, push);
+    }
+}
+
+/// Move ants according to their heading along the links of the nav mesh, see
+/// [LocomotionMode::NavGraph]
+///
+/// Moves are clamped to a quarter of a tile, so an ant crosses at most one link per frame.
+pub fn update_ant_position_on_nav_graph(
+    mut commands: Commands,
+    mut ants: Query<
+        (
+            Entity,
+            &mut AntMovement,
+            &mut Transform,
+            &mut Handle<AntMaterial>,
+            &AntStyle,
+            &Parent,
+        ),
+        (Without<Falling>, Without<OffScreen>),
+    >,
+    holders: Query<&GlobalTransform, Without<NavNode>>,
+    nav_nodes: Query<(&NavNode, &GlobalTransform)>,
+    time: Res<Time>,
+) {
+    let half_tile = TILE_SIZE / 2.;
+    let dt = time.delta_seconds();
+    for (ant, mut ant_movement, mut ant_transform, mut ant_material, ant_style, parent) in
+        ants.iter_mut()
+    {
+        let dt = dt.min(TILE_SIZE / 4. / ant_movement.speed);
+        let step = ant_movement.speed * dt;
+        let heading = ant_movement.heading;
+        let previous_kind = ant_movement.position_kind;
+        // The entities holder is only translated
+        let holder_xy = holders
+            .get(parent.get())
+            .map(|holder| holder.translation().xy())
+            .unwrap_or_default();
+        let mut xy = ant_transform.translation.xy() + holder_xy;
+        let mut z = ant_transform.translation.z;
+        // Distance from the center of a tile at which the ant touches its walls
+        let contact = Vec2::splat(half_tile) - ant_style.size() / 2.;
+        let clipping = ant_style.wall_clipping();
+
+        let current_node = ant_movement.current_node.0;
+        let Ok((nav_node, node_transform)) = nav_nodes.get(current_node) else {
+            continue;
+        };
+        let node_xy = node_transform.translation().xy();
+
+        match (previous_kind, *nav_node) {
+            (
+                AntPositionKind::Background,
+                NavNode::Background {
+                    up,
+                    left,
+                    down,
+                    right,
+                },
+            ) => {
+                xy += heading.xy().normalize_or_zero() * step;
+                let offset = xy - node_xy;
+                let crossed = [
+                    (offset.x, contact.x, right),
+                    (-offset.x, contact.x, left),
+                    (offset.y, contact.y, up),
+                    (-offset.y, contact.y, down),
+                ]
+                .into_iter()
+                .find(|(offset, contact, neighbor)| {
+                    match nav_nodes.get(*neighbor) {
+                        Ok((NavNode::Background { .. }, _)) => *offset > half_tile,
+                        // Climb the wall
+                        Ok(_) => *offset > *contact,
+                        Err(_) => false,
+                    }
+                });
+                if let Some((_, _, neighbor)) = crossed {
+                    enter_node(
+                        &mut ant_movement,
+                        &mut xy,
+                        &mut z,
+                        neighbor,
+                        &nav_nodes,
+                        ant_style,
+                        Vec3::Z,
+                    );
+                }
+            }
+            (
+                AntPositionKind::VerticalWall { .. },
+                NavNode::VerticalEdge {
+                    up,
+                    up_kind,
+                    down,
+                    down_kind,
+                    back,
+                    is_left_side,
+                },
+            ) => {
+                xy.y += heading.y.signum() * step;
+                z = (z + heading.yz().normalize_or_zero()[1] * step).min(TILE_SIZE * WALL_Z_FACTOR);
+                let away = if is_left_side { Vec3::X } else { Vec3::NEG_X };
+                if z <= clipping {
+                    // Go down into the background, away from the wall
+                    xy.x += 2. * clipping * away.x;
+                    z = 0.;
+                    enter_node(
+                        &mut ant_movement,
+                        &mut xy,
+                        &mut z,
+                        back,
+                        &nav_nodes,
+                        ant_style,
+                        away,
+                    );
+                } else {
+                    let offset = xy.y - node_xy.y;
+                    let crossed = [(offset, up, up_kind), (-offset, down, down_kind)]
+                        .into_iter()
+                        .find_map(|(offset, neighbor, kind)| match kind {
+                            EdgeNeighborKind::Straight if offset > half_tile => {
+                                Some((neighbor, Vec3::ZERO))
+                            }
+                            // Walk away from the vertical wall
+                            EdgeNeighborKind::Inward if offset > contact.y => {
+                                Some((neighbor, away))
+                            }
+                            // Go around the corner, towards the side of the vertical wall
+                            EdgeNeighborKind::Outward if offset > half_tile => {
+                                Some((neighbor, -away))
+                            }
+                            _ => None,
+                        });
+                    if let Some((neighbor, push)) = crossed {
+                        enter_node(
+                            &mut ant_movement,
+                            &mut xy,
+                            &mut z,
+                            neighbor,
+                            &nav_nodes,
+                            ant_style,
+                            push,
+                        );
+                    }
+                }
+            }
+            (
+                AntPositionKind::HorizontalWall { .. },
+                NavNode::HorizontalEdge {
+                    left,
+                    right,
+                    back,
+                    is_up_side,
+                },
+            ) => {
+                xy.x += heading.x.signum() * step;
+                z = (z + heading.xz().normalize_or_zero()[1] * step).min(TILE_SIZE * WALL_Z_FACTOR);
+                let away = if is_up_side { Vec3::NEG_Y } else { Vec3::Y };
+                // On the surface, the ant cannot go to the background
+                if back.is_none() && z <= clipping {
+                    z = clipping;
+                    stay_on_surface(&mut ant_movement);
+                }
+                if let (Some(back), true) = (back, z <= clipping) {
+                    // Go down into the background, away from the wall
+                    xy.y += 2. * clipping * away.y;
+                    z = 0.;
+                    enter_node(
+                        &mut ant_movement,
+                        &mut xy,
+                        &mut z,
+                        back,
+                        &nav_nodes,
+                        ant_style,
+                        away,
+                    );
+                } else {
+                    let offset = xy.x - node_xy.x;
+                    for (sign, neighbor) in [(1., right), (-1., left)] {
+                        let offset = sign * offset;
+                        let crossed = match neighbor {
+                            EdgeNeighbor::Straight(neighbor) if offset > half_tile => {
+                                Some((neighbor, Vec3::ZERO))
+                            }
+                            // Walk away from the horizontal wall
+                            EdgeNeighbor::Inward(neighbor) if offset > contact.x => {
+                                Some((neighbor, away))
+                            }
+                            // Go around the corner, towards the side of the horizontal wall
+                            EdgeNeighbor::Outward(neighbor) if offset > half_tile => {
+                                Some((neighbor, -away))
+                            }
+                            // Walk off the ledge of the surface
+                            EdgeNeighbor::None if back.is_none() => {
+                                if offset > half_tile {
+                                    commands.entity(ant).insert(Falling::default());
+                                }
+                                None
+                            }
+                            // End of the map, turn back
+                            EdgeNeighbor::None if offset > contact.x => {
+                                xy.x = node_xy.x + sign * contact.x;
+                                ant_movement.direction = Vec3::X * -sign;
+                                None
+                            }
+                            _ => None,
+                        };
+                        if let Some((neighbor, push)) = crossed {
+                            enter_node(
+                                &mut ant_movement,
+                                &mut xy,
+                                &mut z,
+                                neighbor,
+                                &nav_nodes,
+                                ant_style,
+                                push,
+                            );
+                            break;
+                        }
+                    }
+                }
+            }
+            // The position kind and the current node disagree, put the ant back on its node
+            _ => enter_node(
+                &mut ant_movement,
+                &mut xy,
+                &mut z,
+                current_node,
+                &nav_nodes,
+                ant_style,
+                Vec3::Z,
+            ),
+        }
+
+        ant_transform.translation = (xy - holder_xy).extend(z);
+        if std::mem::discriminant(&previous_kind)
+            != std::mem::discriminant(&ant_movement.position_kind)
+        {
+            *ant_material = ant_material_for(ant_movement.position_kind);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::components::ants::AntColorKind;
+
+    fn ant_style() -> AntStyle {
+        AntStyle::new(
+            1.,
+            AntColorKind::BLACK,
+            AntColorKind::RED,
+            &mut thread_rng(),
+        )
+    }
+
+    #[test]
+    fn snap_to_background_keeps_position() {
+        let (xy, z, position_kind) = snap_to_node(
+            Vec2::new(3., 4.),
+            5.,
+            &NavNode::default(),
+            Vec2::ZERO,
+            &ant_style(),
+        );
+        assert_eq!((xy, z), (Vec2::new(3., 4.), 0.));
+        assert!(matches!(position_kind, AntPositionKind::Background));
+    }
+
+    #[test]
+    fn snap_to_vertical_edge_stays_on_tile() {
+        let ant_style = ant_style();
+        let nav_node = NavNode::VerticalEdge {
+            up: Entity::PLACEHOLDER,
+            up_kind: EdgeNeighborKind::Straight,
+            down: Entity::PLACEHOLDER,
+            down_kind: EdgeNeighborKind::Straight,
+            back: Entity::PLACEHOLDER,
+            is_left_side: true,
+        };
+        let (xy, z, position_kind) = snap_to_node(
+            Vec2::new(0., TILE_SIZE),
+            0.,
+            &nav_node,
+            Vec2::ZERO,
+            &ant_style,
+        );
+        let reach = ant_style.size() / 2. - ant_style.wall_clipping();
+        assert_eq!(xy, Vec2::new(reach.x, TILE_SIZE / 2.));
+        assert_eq!(z, ant_style.wall_clipping());
+        assert!(matches!(
+            position_kind,
+            AntPositionKind::VerticalWall { is_left_side: true }
+        ));
+    }
+
+    #[test]
+    fn snap_to_horizontal_edge_stays_on_tile() {
+        let ant_style = ant_style();
+        let nav_node = NavNode::HorizontalEdge {
+            left: EdgeNeighbor::None,
+            right: EdgeNeighbor::None,
+            back: None,
+            is_up_side: true,
+        };
+        let (xy, z, position_kind) = snap_to_node(
+            Vec2::new(-TILE_SIZE, 0.),
+            TILE_SIZE * WALL_Z_FACTOR * 2.,
+            &nav_node,
+            Vec2::ZERO,
+            &ant_style,
+        );
+        let reach = ant_style.size() / 2. - ant_style.wall_clipping();
+        assert_eq!(xy, Vec2::new(-TILE_SIZE / 2., -reach.y));
+        assert_eq!(z, TILE_SIZE * WALL_Z_FACTOR);
+        assert!(matches!(
+            position_kind,
+            AntPositionKind::HorizontalWall { is_up_side: true }
+        ));
+    }
+}
@@ -2,11 +2,19 @@ use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
 use crate::{
-    components::ants::movement::AntMovement,
+    components::ants::{
+        lod::OffScreen,
+        movement::{
+            falling::{stay_on_surface, Falling},
+            steering::carry_heading_over,
+            AntMovement,
+        },
+        AntStyle,
+    },
     components::nav_mesh::NavNode,
     render::render_ant::{AntMaterial, ANT_MATERIAL_SIDE, ANT_MATERIAL_TOP},
     resources::nav_mesh_lut::NavMeshLUT,
-    ANT_SIZE, ANT_WALL_CLIPPING, TILE_SIZE, WALL_Z_FACTOR,
+    TILE_SIZE, WALL_Z_FACTOR,
 };
 
 #[derive(Debug, Clone, Copy, Reflect)]
@@ -16,25 +24,44 @@ pub enum AntPositionKind {
     HorizontalWall { is_up_side: bool },
 }
 
+/// Ants are seen from the top in the background, and from the side on walls
+pub fn ant_material_for(position_kind: AntPositionKind) -> Handle<AntMaterial> {
+    match position_kind {
+        AntPositionKind::Background => ANT_MATERIAL_TOP.clone(),
+        AntPositionKind::VerticalWall { .. } | AntPositionKind::HorizontalWall { .. } => {
+            ANT_MATERIAL_SIDE.clone()
+        }
+    }
+}
+
 pub fn update_ant_position_kinds(
-    mut ants: Query<(
-        &mut AntMovement,
-        &CollidingEntities,
-        &GlobalTransform,
-        &mut Transform,
-        &mut Handle<AntMaterial>,
-    )>,
+    mut commands: Commands,
+    mut ants: Query<
+        (
+            Entity,
+            &mut AntMovement,
+            &CollidingEntities,
+            &GlobalTransform,
+            &mut Transform,
+            &mut Handle<AntMaterial>,
+            &AntStyle,
+        ),
+        (Without<Falling>, Without<OffScreen>),
+    >,
     nav_nodes: Query<(Entity, &NavNode, &GlobalTransform)>,
     nav_mesh_lut: Res<NavMeshLUT>,
 ) {
     for (
+        ant,
         mut ant_movement,
         colliding_entities,
         ant_transform_global,
         mut ant_transform,
         mut ant_material,
+        ant_style,
     ) in ants.iter_mut()
     {
+        let wall_clipping = ant_style.wall_clipping();
         // Detect walls and update [AntPosition]
         match ant_movement.position_kind {
             AntPositionKind::Background => {
@@ -51,10 +78,11 @@ pub fn update_ant_position_kinds(
                                 *is_up_side,
                                 &mut ant_movement,
                                 &mut ant_transform,
+                                ant_style,
                                 &wall_transform_relative,
                             );
-                            // Give a some z direction to avoid blinking
-                            ant_movement.direction.z = 1.;
+                            // Climb the wall
+                            carry_heading_over(&mut ant_movement, Vec3::Z);
                             ant_movement.current_node = (nav_node_entity, *wall_transform_global);
                         }
                         NavNode::VerticalEdge { is_left_side, .. } => {
@@ -64,18 +92,28 @@ pub fn update_ant_position_kinds(
                                 *is_left_side,
                                 &mut ant_movement,
                                 &mut ant_transform,
+                                ant_style,
                                 &wall_transform_relative,
                             );
-                            // Give a some z direction to avoid blinking
-                            ant_movement.direction.z = 1.;
+                            // Climb the wall
+                            carry_heading_over(&mut ant_movement, Vec3::Z);
                             ant_movement.current_node = (nav_node_entity, *wall_transform_global);
                         }
                     }
                 }
             }
             AntPositionKind::HorizontalWall { is_up_side } => {
-                // Check if the ant has gone into the background
-                if ant_transform.translation.z <= ANT_WALL_CLIPPING {
+                // On the surface, the ant cannot go to the background
+                let on_surface = matches!(
+                    nav_nodes.get(ant_movement.current_node.0),
+                    Ok((_, NavNode::HorizontalEdge { back: None, .. }, _))
+                );
+                if on_surface && ant_transform.translation.z <= wall_clipping {
+                    ant_transform.translation.z = wall_clipping;
+                    stay_on_surface(&mut ant_movement);
+                }
+                // Check if the ant has gone into the background, there is none on the surface
+                if !on_surface && ant_transform.translation.z <= wall_clipping {
                     let NavNode::HorizontalEdge { back, .. } =
                         nav_nodes.get(ant_movement.current_node.0).unwrap().1
                     else {
@@ -86,16 +124,16 @@ pub fn update_ant_position_kinds(
                         // Offset from the wall
                         // TODO: offset from all colliding walls?
                         ant_transform.translation.y += if is_up_side {
-                            -2. * ANT_WALL_CLIPPING
+                            -2. * wall_clipping
                         } else {
-                            2. * ANT_WALL_CLIPPING
+                            2. * wall_clipping
                         };
-                        // Give a push toward foreground to avoid blinking
-                        ant_movement.direction.y = if is_up_side { -1.0 } else { 1.0 };
                         place_ant_on_background(&mut ant_movement, &mut ant_transform);
-                    } else {
-                        // On the surface, the ant cannot go to the background
-                        ant_transform.translation.z = 0.;
+                        // Give a push toward foreground to avoid blinking
+                        carry_heading_over(
+                            &mut ant_movement,
+                            if is_up_side { Vec3::NEG_Y } else { Vec3::Y },
+                        );
                     }
                 }
                 // Check the closest colliding wall
@@ -112,9 +150,14 @@ pub fn update_ant_position_kinds(
                                 *is_left_side,
                                 &mut ant_movement,
                                 &mut ant_transform,
+                                ant_style,
                                 &wall_transform_relative,
                             );
-                            // TODO give some vertical direction away from the horizontal wall
+                            // Walk away from the horizontal wall
+                            carry_heading_over(
+                                &mut ant_movement,
+                                if is_up_side { Vec3::NEG_Y } else { Vec3::Y },
+                            );
                             ant_movement.current_node = (nav_node_entity, *wall_transform_global);
                         }
                         // Otherwise update the transform of wall the ant is currently on
@@ -125,17 +168,27 @@ pub fn update_ant_position_kinds(
                 }
                 // If the ant is no longer colliding with any wall, it means that it went past an outward turn
                 else if colliding_entities.is_empty() {
-                    let new_wall_is_left_side = ant_movement.direction.x > 0.;
+                    let new_wall_is_left_side = ant_movement.heading.x > 0.;
 
                     let current_wall = nav_nodes.get(ant_movement.current_node.0).unwrap();
                     let (wall_entity, wall_node, wall_transform_global) = {
-                        let NavNode::HorizontalEdge { left, right, .. } = current_wall.1 else {
+                        let NavNode::HorizontalEdge {
+                            left, right, back, ..
+                        } = current_wall.1
+                        else {
                             dbg!(current_wall);
                             continue;
                             // FIXME panic!();
                         };
                         let neighbor = if new_wall_is_left_side { right } else { left };
-                        let neighbor = neighbor.get().unwrap(); // If there is no neighbor, there should be a collider to block the ant
+                        // Underground, if there is no neighbor, there should be a collider to block the ant
+                        let Some(neighbor) = neighbor.get() else {
+                            if back.is_none() {
+                                // Walk off the ledge of the surface
+                                commands.entity(ant).insert(Falling::default());
+                            }
+                            continue;
+                        };
                         nav_nodes.get(neighbor).unwrap()
                     };
                     if !matches!(wall_node, NavNode::VerticalEdge { is_left_side, .. } if *is_left_side == new_wall_is_left_side)
@@ -158,24 +211,32 @@ pub fn update_ant_position_kinds(
                             new_wall_is_left_side,
                             &mut ant_movement,
                             &mut ant_transform,
+                            ant_style,
                             &wall_transform_relative,
                         );
-                        // TODO giv eant some direction to avoid blinking
+                        // Go around the corner, towards the side of the horizontal wall
+                        carry_heading_over(
+                            &mut ant_movement,
+                            if is_up_side { Vec3::Y } else { Vec3::NEG_Y },
+                        );
                         ant_movement.current_node = (wall_entity, *wall_transform_global);
                     }
                 }
             }
             AntPositionKind::VerticalWall { is_left_side } => {
                 // Check if the ant has gone into the background
-                if ant_transform.translation.z <= ANT_WALL_CLIPPING {
+                if ant_transform.translation.z <= wall_clipping {
                     ant_transform.translation.x += if is_left_side {
-                        2. * ANT_WALL_CLIPPING
+                        2. * wall_clipping
                     } else {
-                        -2. * ANT_WALL_CLIPPING
+                        -2. * wall_clipping
                     };
-                    ant_movement.direction.x = if is_left_side { 1.0 } else { -1.0 };
-                    place_ant_on_background(&mut ant_movement, &mut ant_transform);
                     place_ant_on_background(&mut ant_movement, &mut ant_transform);
+                    // Give a push toward foreground to avoid blinking
+                    carry_heading_over(
+                        &mut ant_movement,
+                        if is_left_side { Vec3::X } else { Vec3::NEG_X },
+                    );
                 }
                 // Check the closest colliding wall
                 else if let Some((nav_node_entity, nav_node, wall_transform_global)) =
@@ -191,9 +252,14 @@ pub fn update_ant_position_kinds(
                                 *is_up_side,
                                 &mut ant_movement,
                                 &mut ant_transform,
+                                ant_style,
                                 &wall_transform_relative,
                             );
-                            // TODO give some horizontal dir to avoid blinking
+                            // Walk away from the vertical wall
+                            carry_heading_over(
+                                &mut ant_movement,
+                                if is_left_side { Vec3::X } else { Vec3::NEG_X },
+                            );
                             ant_movement.current_node = (nav_node_entity, *wall_transform_global);
                         }
                         // Otherwise update the transform of wall the ant is currently on
@@ -204,7 +270,7 @@ pub fn update_ant_position_kinds(
                 }
                 // If the ant is no longer colliding with any wall, it means that it went past an outward turn
                 else if colliding_entities.is_empty() {
-                    let new_wall_is_up_side = ant_movement.direction.y < 0.;
+                    let new_wall_is_up_side = ant_movement.heading.y < 0.;
 
                     let current_wall = nav_nodes.get(ant_movement.current_node.0).unwrap();
                     let (wall_entity, wall_node, wall_transform_global) = {
@@ -235,9 +301,14 @@ pub fn update_ant_position_kinds(
                             new_wall_is_up_side,
                             &mut ant_movement,
                             &mut ant_transform,
+                            ant_style,
                             &wall_transform_relative,
                         );
-                        // TODO give some horizontal dir to avoid blinking
+                        // Go around the corner, towards the side of the vertical wall
+                        carry_heading_over(
+                            &mut ant_movement,
+                            if is_left_side { Vec3::NEG_X } else { Vec3::X },
+                        );
                         ant_movement.current_node = (wall_entity, *wall_transform_global);
                     }
                 }
@@ -256,12 +327,7 @@ pub fn update_ant_position_kinds(
             ant_movement.current_node = (background_entity, *background_entity_transform);
         }
         // Update material
-        *ant_material = match ant_movement.position_kind {
-            AntPositionKind::Background => ANT_MATERIAL_TOP.clone(),
-            AntPositionKind::VerticalWall { .. } | AntPositionKind::HorizontalWall { .. } => {
-                ANT_MATERIAL_SIDE.clone()
-            }
-        };
+        *ant_material = ant_material_for(ant_movement.position_kind);
     }
 }
 
@@ -296,9 +362,9 @@ pub fn assert_ants(
     }
 }
 
-/// Move ants according to their direction and the constraints of [AntPositionKind]
+/// Move ants according to their heading and the constraints of [AntPositionKind]
 pub fn update_ant_position(
-    mut ants: Query<(&AntMovement, &mut Transform)>,
+    mut ants: Query<(&AntMovement, &mut Transform), (Without<Falling>, Without<OffScreen>)>,
     time: Res<Time>,
     nav_mesh_lut: Res<NavMeshLUT>,
 ) {
@@ -308,15 +374,15 @@ pub fn update_ant_position(
         match ant_movement.position_kind {
             AntPositionKind::Background => {
                 let delta_xy =
-                    ant_movement.direction.xy().normalize_or_zero() * ant_movement.speed * dt;
+                    ant_movement.heading.xy().normalize_or_zero() * ant_movement.speed * dt;
                 ant_transform.translation.x += delta_xy.x;
                 ant_transform.translation.y += delta_xy.y;
             }
             AntPositionKind::VerticalWall { .. } => {
-                // Vertical speed is the ant's full speed, while speed in Z axis is a projection of the desired direction on YZ
+                // Vertical speed is the ant's full speed, while speed in Z axis is a projection of the heading on YZ
                 let delta_yz =
-                    ant_movement.direction.yz().normalize_or_zero() * ant_movement.speed * dt;
-                let delta_y = ant_movement.direction.y.signum() * ant_movement.speed * dt;
+                    ant_movement.heading.yz().normalize_or_zero() * ant_movement.speed * dt;
+                let delta_y = ant_movement.heading.y.signum() * ant_movement.speed * dt;
                 ant_transform.translation.y += delta_y;
                 ant_transform.translation.z += delta_yz[1];
                 if ant_transform.translation.z > WALL_Z_FACTOR * TILE_SIZE {
@@ -324,10 +390,10 @@ pub fn update_ant_position(
                 }
             }
             AntPositionKind::HorizontalWall { .. } => {
-                // Horizontal speed is the ant's full speed, while speed in Z axis is a projection of the desired direction on XZ
+                // Horizontal speed is the ant's full speed, while speed in Z axis is a projection of the heading on XZ
                 let delta_y =
-                    ant_movement.direction.xz().normalize_or_zero() * ant_movement.speed * dt;
-                let delta_x = ant_movement.direction.x.signum() * ant_movement.speed * dt;
+                    ant_movement.heading.xz().normalize_or_zero() * ant_movement.speed * dt;
+                let delta_x = ant_movement.heading.x.signum() * ant_movement.speed * dt;
                 ant_transform.translation.x += delta_x;
                 ant_transform.translation.z += delta_y[1];
                 if ant_transform.translation.z > WALL_Z_FACTOR * TILE_SIZE {
@@ -381,19 +447,56 @@ fn find_closest_wall<'a>(
         })
 }
 
+/// Transform (relative to `parent_pos`) and [AntPositionKind] of an ant standing on a [NavNode]
+pub fn ant_transform_on_nav_node(
+    nav_node: &NavNode,
+    nav_node_pos: &GlobalTransform,
+    parent_pos: &GlobalTransform,
+    ant_style: &AntStyle,
+) -> (Transform, AntPositionKind) {
+    let ant_size = ant_style.size();
+    let wall_clipping = ant_style.wall_clipping();
+    let mut transform = nav_node_pos.reparented_to(parent_pos);
+    let position_kind = match nav_node {
+        NavNode::Background { .. } => {
+            transform.translation.z = 0.;
+            AntPositionKind::Background
+        }
+        NavNode::VerticalEdge { is_left_side, .. } => {
+            transform.translation.z = TILE_SIZE * WALL_Z_FACTOR;
+            transform.translation.x +=
+                (ant_size.x / 2. - wall_clipping) * if *is_left_side { 1. } else { -1. };
+            AntPositionKind::VerticalWall {
+                is_left_side: *is_left_side,
+            }
+        }
+        NavNode::HorizontalEdge { is_up_side, .. } => {
+            transform.translation.z = TILE_SIZE * WALL_Z_FACTOR;
+            transform.translation.y +=
+                (ant_size.y / 2. - wall_clipping) * if *is_up_side { -1. } else { 1. };
+            AntPositionKind::HorizontalWall {
+                is_up_side: *is_up_side,
+            }
+        }
+    };
+    (transform, position_kind)
+}
+
 fn place_ant_on_horizontal_wall(
     is_up_side: bool,
     ant_movement: &mut AntMovement,
     ant_transform: &mut Transform,
+    ant_style: &AntStyle,
     wall_transform_relative: &Transform,
 ) {
     // Change position kind
     ant_movement.position_kind = AntPositionKind::HorizontalWall { is_up_side };
     // Re-place ant on the wall
-    let offset = (ANT_SIZE.y / 2. - ANT_WALL_CLIPPING) * if is_up_side { -1. } else { 1. };
+    let wall_clipping = ant_style.wall_clipping();
+    let offset = (ant_style.size().y / 2. - wall_clipping) * if is_up_side { -1. } else { 1. };
     ant_transform.translation.y += wall_transform_relative.translation.y + offset;
-    if ant_transform.translation.z < ANT_WALL_CLIPPING {
-        ant_transform.translation.z = ANT_WALL_CLIPPING;
+    if ant_transform.translation.z < wall_clipping {
+        ant_transform.translation.z = wall_clipping;
     }
 }
 
@@ -401,15 +504,17 @@ fn place_ant_on_vertical_wall(
     is_left_side: bool,
     ant_movement: &mut AntMovement,
     ant_transform: &mut Transform,
+    ant_style: &AntStyle,
     wall_transform_relative: &Transform,
 ) {
     // Change position kind
     ant_movement.position_kind = AntPositionKind::VerticalWall { is_left_side };
     // Re-place ant on the wall
-    let offset = (ANT_SIZE.x / 2. - ANT_WALL_CLIPPING) * if is_left_side { 1. } else { -1. };
+    let wall_clipping = ant_style.wall_clipping();
+    let offset = (ant_style.size().x / 2. - wall_clipping) * if is_left_side { 1. } else { -1. };
     ant_transform.translation.x += wall_transform_relative.translation.x + offset;
-    if ant_transform.translation.z < ANT_WALL_CLIPPING {
-        ant_transform.translation.z = ANT_WALL_CLIPPING;
+    if ant_transform.translation.z < wall_clipping {
+        ant_transform.translation.z = wall_clipping;
     }
 }
 
@@ -0,0 +1,96 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+
+use crate::components::ants::{
+    lod::OffScreen,
+    movement::{direction::turning_axis, position::AntPositionKind, AntMovement},
+};
+
+/// How fast ants can turn their heading towards their desired direction
+#[derive(Debug, Clone, Resource, Reflect)]
+pub struct SteeringConfig {
+    /// In radians per second
+    pub max_angular_velocity: f32,
+    /// In radians per second squared
+    pub max_angular_acceleration: f32,
+}
+
+impl Default for SteeringConfig {
+    fn default() -> Self {
+        Self {
+            max_angular_velocity: 2. * PI,
+            max_angular_acceleration: 8. * PI,
+        }
+    }
+}
+
+/// Remove the component of `v` normal to the surface the ant walks on
+pub fn project_on_surface(v: Vec3, position_kind: AntPositionKind) -> Vec3 {
+    match position_kind {
+        AntPositionKind::Background => Vec3::new(v.x, v.y, 0.),
+        AntPositionKind::VerticalWall { .. } => Vec3::new(0., v.y, v.z),
+        AntPositionKind::HorizontalWall { .. } => Vec3::new(v.x, 0., v.z),
+    }
+}
+
+/// Carry the heading and the desired direction of an ant over a change of [AntPositionKind]:
+/// the component along the axis shared by both surfaces is kept, and the one that was lost is
+/// replaced by `push`, so the ant keeps going the same way instead of blinking between surfaces
+pub fn carry_heading_over(ant_movement: &mut AntMovement, push: Vec3) {
+    let position_kind = ant_movement.position_kind;
+    let carry = |v: Vec3| {
+        let carried = project_on_surface(v, position_kind);
+        let carried = carried - carried.project_onto_normalized(push) + push;
+        carried.try_normalize().unwrap_or(push)
+    };
+    ant_movement.heading = carry(ant_movement.heading);
+    ant_movement.direction = carry(ant_movement.direction);
+}
+
+/// Turn the heading of ants towards their desired direction, offset by the crowd avoidance,
+/// within the limits of [SteeringConfig]
+pub fn update_ant_steering(
+    mut ants: Query<&mut AntMovement, Without<OffScreen>>,
+    config: Res<SteeringConfig>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    if dt <= 0. {
+        return;
+    }
+    for mut ant_movement in ants.iter_mut() {
+        let position_kind = ant_movement.position_kind;
+        let desired = ant_movement.direction.normalize_or_zero() + ant_movement.avoidance;
+        let Some(desired) = project_on_surface(desired, position_kind).try_normalize() else {
+            ant_movement.angular_velocity = 0.;
+            continue;
+        };
+        let Some(heading) = project_on_surface(ant_movement.heading, position_kind).try_normalize()
+        else {
+            ant_movement.heading = desired;
+            continue;
+        };
+
+        let angle = heading.angle_between(desired);
+        // U-turns have no preferred side, turn on the surface
+        let axis = heading
+            .cross(desired)
+            .try_normalize()
+            .unwrap_or_else(|| turning_axis(position_kind));
+
+        // Accelerate, but slow down soon enough not to overshoot the desired direction
+        let braking = (2. * config.max_angular_acceleration * angle).sqrt();
+        let angular_velocity = (ant_movement.angular_velocity
+            + config.max_angular_acceleration * dt)
+            .min(config.max_angular_velocity)
+            .min(braking);
+        let step = (angular_velocity * dt).min(angle);
+
+        ant_movement.angular_velocity = angular_velocity;
+        ant_movement.heading =
+            project_on_surface(Quat::from_axis_angle(axis, step) * heading, position_kind)
+                .try_normalize()
+                .unwrap_or(desired);
+    }
+}
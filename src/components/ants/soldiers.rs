@@ -0,0 +1,138 @@
+use bevy::{prelude::*, utils::HashSet};
+use rand::{thread_rng, Rng};
+
+use crate::{
+    components::{
+        ants::{
+            dead_ants::{kill_ant, Dying},
+            job::Job,
+            live_ants::LiveAnt,
+            lod::{lod_delta, OffScreen},
+            movement::AntMovement,
+            zombants::ZombAnt,
+        },
+        nursery::Nursery,
+        player::{Player, PlayerHealth},
+    },
+    render::player_animation::Explosion,
+    TILE_SIZE,
+};
+
+/// Anger of the colony towards the player, raised by explosions near the nest
+#[derive(Debug, Default, Clone, Copy, Resource, Reflect)]
+pub struct ColonyHostility {
+    pub level: f32,
+}
+
+#[derive(Debug, Clone, Resource, Reflect)]
+pub struct SoldierConfig {
+    /// Probability per second for a patrolling ant to kill a zombant on its node
+    pub attack_rate: f32,
+    /// Distance from a room of the nest under which explosions anger the colony
+    pub colony_radius: f32,
+    /// Hostility added by each explosion near the nest
+    pub hostility_per_explosion: f32,
+    /// Hostility lost per second
+    pub hostility_decay: f32,
+    /// Hostility above which the patrols attack the player
+    pub hostile_threshold: f32,
+    /// Distance under which hostile patrols chase the player
+    pub chase_radius: f32,
+    /// Distance under which hostile patrols bite the player
+    pub bite_radius: f32,
+    /// Health taken from the player per second by each biting patrol
+    pub bite_damage: f32,
+}
+
+impl Default for SoldierConfig {
+    fn default() -> Self {
+        Self {
+            attack_rate: 0.5,
+            colony_radius: 15. * TILE_SIZE,
+            hostility_per_explosion: 1.,
+            hostility_decay: 0.05,
+            hostile_threshold: 2.,
+            chase_radius: 12. * TILE_SIZE,
+            bite_radius: TILE_SIZE,
+            bite_damage: 0.1,
+        }
+    }
+}
+
+/// Explosions close to the rooms of the nest make the colony hostile for a while
+pub fn update_colony_hostility(
+    explosions: Query<&GlobalTransform, Added<Explosion>>,
+    nursery: Res<Nursery>,
+    mut hostility: ResMut<ColonyHostility>,
+    config: Res<SoldierConfig>,
+    time: Res<Time>,
+) {
+    let mut level = (hostility.level - config.hostility_decay * time.delta_seconds()).max(0.);
+    for explosion_transform in explosions.iter() {
+        let pos = explosion_transform.translation().xy();
+        if nursery
+            .rooms
+            .iter()
+            .any(|room| room.center.distance(pos) < config.colony_radius)
+        {
+            level += config.hostility_per_explosion;
+        }
+    }
+    if level != hostility.level {
+        hostility.level = level;
+    }
+}
+
+/// Patrolling ants kill the zombants they meet, and chase and bite the player when the colony is
+/// hostile
+pub fn update_soldiers(
+    mut commands: Commands,
+    mut patrols: Query<(&mut AntMovement, &GlobalTransform, Option<&OffScreen>), With<LiveAnt>>,
+    zombants: Query<(Entity, &AntMovement), (With<ZombAnt>, Without<LiveAnt>, Without<Dying>)>,
+    mut players: Query<(&GlobalTransform, &mut PlayerHealth), With<Player>>,
+    hostility: Res<ColonyHostility>,
+    config: Res<SoldierConfig>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    let mut rng = thread_rng();
+    let mut player = players.get_single_mut().ok();
+    let hostile = hostility.level > config.hostile_threshold;
+    let mut killed = HashSet::new();
+
+    for (mut ant_movement, ant_transform, off_screen) in patrols.iter_mut() {
+        if ant_movement.goal.job != Job::Patrol {
+            continue;
+        }
+        let Some(dt) = lod_delta(off_screen, dt) else {
+            continue;
+        };
+
+        // Attack the zombants on the same node
+        if rng.gen::<f32>() < config.attack_rate * dt {
+            if let Some((zombant, _)) = zombants.iter().find(|(zombant, zombant_movement)| {
+                zombant_movement.current_node.0 == ant_movement.current_node.0
+                    && !killed.contains(zombant)
+            }) {
+                killed.insert(zombant);
+                kill_ant(&mut commands, zombant);
+            }
+        }
+
+        // Chase the player
+        if !hostile {
+            continue;
+        }
+        let Some((player_transform, player_health)) = player.as_mut() else {
+            continue;
+        };
+        let to_player = player_transform.translation().xy() - ant_transform.translation().xy();
+        let distance = to_player.length();
+        if distance < config.bite_radius {
+            player_health.health -= config.bite_damage * dt;
+        } else if distance < config.chase_radius {
+            ant_movement.direction = to_player.normalize().extend(0.);
+            ant_movement.last_direction_update = time.elapsed_seconds();
+        }
+    }
+}
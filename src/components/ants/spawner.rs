@@ -0,0 +1,429 @@
+use std::str::FromStr;
+
+use bevy::{ecs::system::Command, prelude::*, render::view::RenderLayers};
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::{rngs::ThreadRng, thread_rng, Rng};
+
+use crate::{
+    components::{
+        ants::{
+            boss::{BossConfig, QueenBoss},
+            caste::Caste,
+            colony_queen::ColonyQueen,
+            evasion::QueenEvasion,
+            goal::AntGoal,
+            job::Job,
+            life::{AntLife, LifeConfig},
+            live_ants::LiveAnt,
+            movement::{
+                position::{ant_transform_on_nav_node, AntPositionKind},
+                AntMovement,
+            },
+            zombants::{ZombAnt, ZombAntQueen},
+            AntColorKind, AntStyle,
+        },
+        nav_mesh::NavNode,
+    },
+    render::render_ant::{AntMaterialBundle, ANT_MATERIAL_SIDE, ANT_MATERIAL_TOP, ANT_MESH2D},
+    resources::nav_mesh_lut::NavMeshLUT,
+    ANT_SPEED, COLLISION_GROUP_ANTS, COLLISION_GROUP_EXPLOSION, COLLISION_GROUP_PLAYER_SENSOR,
+    COLLISION_GROUP_WALLS, RENDERLAYER_ANTS,
+};
+
+/// Kind of ant, deciding which marker component is added on spawn
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum AntArchetype {
+    #[default]
+    Live,
+    Zombant,
+    ZombantQueen,
+    ColonyQueen,
+}
+
+impl FromStr for AntArchetype {
+    type Err = String;
+
+    /// Queens are not accepted, they are spawned by their own systems
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "live" => Ok(Self::Live),
+            "zombant" => Ok(Self::Zombant),
+            _ => Err(format!("unknown archetype `{s}`")),
+        }
+    }
+}
+
+/// Components shared by every ant walking on the nav mesh
+#[derive(Bundle)]
+pub struct AntBundle {
+    pub ant_movement: AntMovement,
+    pub ant_style: AntStyle,
+    pub caste: Caste,
+    pub material: AntMaterialBundle,
+    pub collider: Collider,
+    pub sensor: Sensor,
+    pub active_events: ActiveEvents,
+    pub active_collisions: ActiveCollisionTypes,
+    pub colliding_entities: CollidingEntities,
+    pub collision_groups: CollisionGroups,
+    pub render_layers: RenderLayers,
+}
+
+impl AntBundle {
+    pub fn new(
+        ant_movement: AntMovement,
+        ant_style: AntStyle,
+        caste: Caste,
+        transform: Transform,
+    ) -> Self {
+        let material = AntMaterialBundle {
+            mesh: ANT_MESH2D,
+            material: match ant_movement.position_kind {
+                AntPositionKind::Background => ANT_MATERIAL_TOP,
+                AntPositionKind::VerticalWall { .. } | AntPositionKind::HorizontalWall { .. } => {
+                    ANT_MATERIAL_SIDE
+                }
+            },
+            transform,
+            ..default()
+        };
+        let size = ant_style.size();
+        Self {
+            ant_movement,
+            ant_style,
+            caste,
+            material,
+            collider: Collider::cuboid(size.x / 2., size.y / 2.),
+            sensor: Sensor,
+            active_events: ActiveEvents::COLLISION_EVENTS,
+            active_collisions: ActiveCollisionTypes::STATIC_STATIC,
+            colliding_entities: Default::default(),
+            collision_groups: CollisionGroups::new(
+                COLLISION_GROUP_ANTS,
+                COLLISION_GROUP_PLAYER_SENSOR | COLLISION_GROUP_WALLS | COLLISION_GROUP_EXPLOSION,
+            ),
+            render_layers: RENDERLAYER_ANTS,
+        }
+    }
+}
+
+/// Builder spawning any [AntArchetype] on a [NavNode]
+///
+/// The ant is placed on the node (clipping into the wall if it is an edge) and parented to the
+/// LDtk "Entities" holder. Colors and direction are randomized unless set.
+/// Spawning is done through a [Command], so it can be used from systems or with a `&mut World`.
+#[derive(Debug, Clone, Copy)]
+pub struct AntSpawner {
+    pub archetype: AntArchetype,
+    pub nav_node: Entity,
+    pub caste: Caste,
+    pub goal: AntGoal,
+    pub speed: f32,
+    pub scale: f32,
+    pub direction: Option<Vec3>,
+    pub color_primary_kind: Option<AntColorKind>,
+    pub color_secondary_kind: Option<AntColorKind>,
+}
+
+impl AntSpawner {
+    pub fn new(archetype: AntArchetype, nav_node: Entity) -> Self {
+        Self {
+            archetype,
+            nav_node,
+            caste: Caste::default(),
+            goal: AntGoal::default(),
+            speed: ANT_SPEED,
+            scale: 1.,
+            direction: None,
+            color_primary_kind: None,
+            color_secondary_kind: None,
+        }
+    }
+
+    /// The caste scales the [speed](Self::speed) and [scale](Self::scale) of the ant
+    pub fn caste(mut self, caste: Caste) -> Self {
+        self.caste = caste;
+        self
+    }
+
+    pub fn job(mut self, job: Job) -> Self {
+        self.goal.job = job;
+        self
+    }
+
+    pub fn goal(mut self, goal: AntGoal) -> Self {
+        self.goal = goal;
+        self
+    }
+
+    pub fn speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn direction(mut self, direction: Vec3) -> Self {
+        self.direction = Some(direction);
+        self
+    }
+
+    /// The secondary color is picked to match the primary one if not set
+    pub fn colors(
+        mut self,
+        color_primary_kind: AntColorKind,
+        color_secondary_kind: Option<AntColorKind>,
+    ) -> Self {
+        self.color_primary_kind = Some(color_primary_kind);
+        self.color_secondary_kind = color_secondary_kind;
+        self
+    }
+
+    /// Build the components of the ant, with a transform relative to `entities_holder_pos`
+    pub fn build(
+        &self,
+        nav_node: &NavNode,
+        nav_node_pos: &GlobalTransform,
+        entities_holder_pos: &GlobalTransform,
+        rng: &mut ThreadRng,
+    ) -> AntBundle {
+        let color_primary_kind = self
+            .color_primary_kind
+            .unwrap_or_else(|| AntColorKind::new_random(rng));
+        let color_secondary_kind = self
+            .color_secondary_kind
+            .unwrap_or_else(|| AntColorKind::new_random_from_primary(rng, &color_primary_kind));
+        let ant_style = AntStyle::new(
+            self.scale * self.caste.scale(),
+            color_primary_kind,
+            color_secondary_kind,
+            rng,
+        );
+        let (transform, position_kind) =
+            ant_transform_on_nav_node(nav_node, nav_node_pos, entities_holder_pos, &ant_style);
+        let direction = self.direction.unwrap_or_else(|| random_direction(rng));
+        let ant_movement = AntMovement {
+            position_kind,
+            speed: self.speed * self.caste.speed_factor(),
+            direction,
+            heading: direction,
+            angular_velocity: 0.,
+            avoidance: Vec3::ZERO,
+            current_node: (self.nav_node, *nav_node_pos),
+            goal: self.goal,
+            last_direction_update: 0.0,
+        };
+        AntBundle::new(ant_movement, ant_style, self.caste, transform)
+    }
+
+    /// Queue the spawn of the ant, returning its future entity
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        let entity = commands.spawn_empty().id();
+        commands.add(SpawnAnt {
+            entity,
+            spawner: self,
+        });
+        entity
+    }
+
+    /// Spawn the ant immediately
+    pub fn spawn_in_world(self, world: &mut World) -> Option<Entity> {
+        let entity = world.spawn_empty().id();
+        self.insert_in_world(entity, world).then_some(entity)
+    }
+
+    fn insert_in_world(self, entity: Entity, world: &mut World) -> bool {
+        let Some((entities_holder, entities_holder_pos)) = find_entities_holder(world) else {
+            error!("Cannot spawn an ant: the level has no \"Entities\" holder");
+            world.despawn(entity);
+            return false;
+        };
+        let (Some(nav_node), Some(nav_node_pos)) = (
+            world.get::<NavNode>(self.nav_node).copied(),
+            world.get::<GlobalTransform>(self.nav_node).copied(),
+        ) else {
+            error!("Cannot spawn an ant: {:?} is not a nav node", self.nav_node);
+            world.despawn(entity);
+            return false;
+        };
+        let mut rng = thread_rng();
+        let bundle = self.build(&nav_node, &nav_node_pos, &entities_holder_pos, &mut rng);
+        let life_config = world
+            .get_resource::<LifeConfig>()
+            .cloned()
+            .unwrap_or_default();
+        let boss_config = world
+            .get_resource::<BossConfig>()
+            .cloned()
+            .unwrap_or_default();
+        let mut ant = world.entity_mut(entity);
+        ant.insert(bundle);
+        match self.archetype {
+            AntArchetype::Live => {
+                ant.insert((LiveAnt {}, AntLife::new(&life_config, &mut rng)));
+            }
+            AntArchetype::Zombant => {
+                ant.insert(ZombAnt {});
+            }
+            AntArchetype::ZombantQueen => {
+                ant.insert((
+                    ZombAntQueen::default(),
+                    QueenEvasion::default(),
+                    QueenBoss::new(&boss_config),
+                ));
+            }
+            AntArchetype::ColonyQueen => {
+                ant.insert(ColonyQueen::default());
+            }
+        }
+        ant.set_parent(entities_holder);
+        true
+    }
+}
+
+struct SpawnAnt {
+    entity: Entity,
+    spawner: AntSpawner,
+}
+
+impl Command for SpawnAnt {
+    fn apply(self, world: &mut World) {
+        self.spawner.insert_in_world(self.entity, world);
+    }
+}
+
+/// Find the entity holding the LDtk entities of the level, which ants are parented to
+pub fn find_entities_holder(world: &mut World) -> Option<(Entity, GlobalTransform)> {
+    let mut levels = world.query_filtered::<&Children, With<LevelIid>>();
+    let mut named_transforms = world.query::<(&Name, &GlobalTransform)>();
+    let level_children = levels.iter(world).next()?;
+    level_children.iter().find_map(|child| {
+        let (name, transform) = named_transforms.get(world, *child).ok()?;
+        (name.as_str() == "Entities").then_some((*child, *transform))
+    })
+}
+
+pub fn random_direction(rng: &mut ThreadRng) -> Vec3 {
+    Vec3::new(
+        rng.gen::<f32>() - 0.5,
+        rng.gen::<f32>() - 0.5,
+        rng.gen::<f32>() - 0.5,
+    )
+    .normalize()
+}
+
+/// Ant placed by hand in the LDtk level, spawned on the closest [NavNode] once the nav mesh exists
+///
+/// The LDtk "Ant" entity has the optional fields `Archetype`, `Caste`, `Job`, `PrimaryColor` and
+/// `SecondaryColor` (enums), and `Speed` and `Scale` (floats).
+#[derive(Debug, Clone, Copy, Component)]
+pub struct AntSpawnPoint {
+    pub archetype: AntArchetype,
+    pub caste: Caste,
+    pub job: Job,
+    pub color_primary_kind: Option<AntColorKind>,
+    pub color_secondary_kind: Option<AntColorKind>,
+    pub speed: f32,
+    pub scale: f32,
+}
+
+impl LdtkEntity for AntSpawnPoint {
+    fn bundle_entity(
+        entity_instance: &EntityInstance,
+        _layer_instance: &LayerInstance,
+        _tileset: Option<&Handle<Image>>,
+        _tileset_definition: Option<&TilesetDefinition>,
+        _asset_server: &AssetServer,
+        _texture_atlases: &mut Assets<TextureAtlas>,
+    ) -> Self {
+        fn parse_enum_field<T: FromStr<Err = String>>(
+            entity_instance: &EntityInstance,
+            identifier: &str,
+        ) -> Option<T> {
+            let value = entity_instance
+                .get_maybe_enum_field(identifier)
+                .ok()?
+                .as_ref()?;
+            value
+                .parse()
+                .map_err(|err| warn!("Ignoring field {identifier} of placed ant: {err}"))
+                .ok()
+        }
+        let get_float_field = |identifier| {
+            entity_instance
+                .get_maybe_float_field(identifier)
+                .ok()
+                .copied()
+                .flatten()
+        };
+
+        Self {
+            archetype: parse_enum_field(entity_instance, "Archetype").unwrap_or_default(),
+            caste: parse_enum_field(entity_instance, "Caste").unwrap_or_default(),
+            job: parse_enum_field(entity_instance, "Job").unwrap_or_default(),
+            color_primary_kind: parse_enum_field(entity_instance, "PrimaryColor"),
+            color_secondary_kind: parse_enum_field(entity_instance, "SecondaryColor"),
+            speed: get_float_field("Speed").unwrap_or(ANT_SPEED),
+            scale: get_float_field("Scale").unwrap_or(1.),
+        }
+    }
+}
+
+impl AntSpawnPoint {
+    pub fn spawner(&self, nav_node: Entity) -> AntSpawner {
+        let mut spawner = AntSpawner::new(self.archetype, nav_node)
+            .caste(self.caste)
+            .job(self.job)
+            .speed(self.speed)
+            .scale(self.scale);
+        if let Some(color_primary_kind) = self.color_primary_kind {
+            spawner = spawner.colors(color_primary_kind, self.color_secondary_kind);
+        }
+        spawner
+    }
+}
+
+/// Replace the [AntSpawnPoint]s placed in LDtk with ants on the closest nav node
+pub fn spawn_placed_ants(
+    mut commands: Commands,
+    spawn_points: Query<(Entity, &AntSpawnPoint, &GlobalTransform)>,
+    nav_nodes: Query<(Entity, &GlobalTransform), With<NavNode>>,
+    nav_mesh_lut: Res<NavMeshLUT>,
+) {
+    for (entity, spawn_point, spawn_point_pos) in spawn_points.iter() {
+        let pos = spawn_point_pos.translation().xy();
+        let distance = |nav_node: Entity| {
+            nav_nodes
+                .get(nav_node)
+                .map(|(_, nav_node_pos)| nav_node_pos.translation().xy().distance(pos))
+                .unwrap_or(f32::INFINITY)
+        };
+        // Look for the background tile and its edges first, then fallback to all the nodes
+        let closest_nav_node = nav_mesh_lut
+            .get_tile_entity(pos)
+            .map(|(tile, tile_index)| {
+                let edges = nav_mesh_lut.get_tile_edges(tile_index);
+                [Some(tile), edges.up, edges.left, edges.down, edges.right]
+                    .into_iter()
+                    .flatten()
+                    .min_by(|a, b| distance(*a).total_cmp(&distance(*b)))
+                    .unwrap()
+            })
+            .or_else(|| {
+                nav_nodes
+                    .iter()
+                    .map(|(nav_node, _)| nav_node)
+                    .min_by(|a, b| distance(*a).total_cmp(&distance(*b)))
+            });
+        if let Some(nav_node) = closest_nav_node {
+            spawn_point.spawner(nav_node).spawn(&mut commands);
+        } else {
+            warn!("Cannot find a nav node for the ant placed at {pos}");
+        }
+        commands.entity(entity).remove_parent().despawn();
+    }
+}
@@ -6,24 +6,36 @@ use rand::{
 };
 
 use crate::{
-    components::cocoons::Cocoon,
+    components::{ants::zombants::ZombAntQueen, cocoons::Cocoon},
     render::render_cocoon::{CocoonMaterial, COCOON_MATERIAL_CLUE},
     CLUES_NUMBER, COCOON_ROOMS, COLLISION_GROUP_CLUE, COLLISION_GROUP_PLAYER_SENSOR,
 };
 
-#[derive(Debug, Default, Clone, Copy, Component, Reflect)]
-pub struct Clue;
+#[derive(Debug, Clone, Copy, Component, Reflect)]
+pub struct Clue {
+    /// Zomb'Ant Queen whose disguise is revealed by the clue
+    pub queen: Entity,
+}
 
+/// Place [CLUES_NUMBER] clues for each Zomb'Ant Queen of the level
 pub fn place_clues(
     mut commands: Commands,
     mut cocoons: Query<(Entity, &mut Cocoon, &mut Handle<CocoonMaterial>)>,
+    queens: Query<Entity, With<ZombAntQueen>>,
 ) {
     let mut rng = thread_rng();
-    let selected_rooms = COCOON_ROOMS.choose_multiple(&mut rng, CLUES_NUMBER);
-    for room in selected_rooms {
+    let selected_rooms = queens
+        .iter()
+        .flat_map(|queen| {
+            COCOON_ROOMS
+                .choose_multiple(&mut rng, CLUES_NUMBER)
+                .map(move |room| (queen, room))
+        })
+        .collect::<Vec<_>>();
+    for (queen, room) in selected_rooms {
         let Some((entity, mut cocoon, mut material)) = cocoons
             .iter_mut()
-            .filter(|(_, cocoon, _)| cocoon.room == *room)
+            .filter(|(_, cocoon, _)| cocoon.room == *room && !cocoon.is_clue)
             .choose(&mut rng)
         else {
             warn!("Room {room} has no cocoons");
@@ -32,7 +44,7 @@ pub fn place_clues(
         cocoon.is_clue = true;
         *material = COCOON_MATERIAL_CLUE;
         commands.entity(entity).insert((
-            Clue,
+            Clue { queen },
             Collider::capsule_x(6., 3.),
             ActiveEvents::COLLISION_EVENTS,
             ActiveCollisionTypes::STATIC_STATIC,
@@ -2,9 +2,18 @@ use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
 use bevy_ecs_ldtk::prelude::*;
 
 use crate::{
+    components::{
+        ants::{
+            job::Job,
+            spawner::{AntArchetype, AntSpawner},
+        },
+        nursery::Carried,
+        object::{take_from_storage, Object},
+    },
     render::render_cocoon::{
         CocoonMaterialBundle, COCOON_MATERIAL, COCOON_MATERIAL_CLUE, COCOON_MESH2D,
     },
+    resources::nav_mesh_lut::NavMeshLUT,
     COCOON_ROOMS,
 };
 
@@ -19,6 +28,29 @@ pub struct CocoonBundle {
 pub struct Cocoon {
     pub is_clue: bool,
     pub room: u8,
+    /// Seconds of development, the cocoon hatches after [CocoonConfig::development_time]
+    pub development: f32,
+    /// Hatches into a zombant
+    pub infected: bool,
+    /// Laid by the colony queen, only those develop
+    pub laid: bool,
+}
+
+#[derive(Debug, Clone, Resource, Reflect)]
+pub struct CocoonConfig {
+    /// Seconds of development needed to hatch, when fed
+    pub development_time: f32,
+    /// Food taken from the storage by each developing cocoon, per second
+    pub food_consumption: f32,
+}
+
+impl Default for CocoonConfig {
+    fn default() -> Self {
+        Self {
+            development_time: 120.,
+            food_consumption: 0.05,
+        }
+    }
 }
 
 impl Default for CocoonBundle {
@@ -30,7 +62,11 @@ impl Default for CocoonBundle {
 impl CocoonBundle {
     pub fn new(is_clue: bool, room: u8) -> Self {
         Self {
-            cocoon: Cocoon { is_clue, room },
+            cocoon: Cocoon {
+                is_clue,
+                room,
+                ..default()
+            },
             material: MaterialMesh2dBundle {
                 mesh: COCOON_MESH2D.clone(),
                 material: if is_clue {
@@ -58,3 +94,54 @@ impl LdtkEntity for CocoonBundle {
         Self::new(false, room)
     }
 }
+
+/// Develop cocoons with food from the storage, and hatch them into new ants
+///
+/// Only the cocoons [laid](Cocoon::laid) by the colony queen develop, and not while carried.
+pub fn update_cocoons(
+    mut commands: Commands,
+    mut cocoons: Query<(Entity, &mut Cocoon, &GlobalTransform), Without<Carried>>,
+    mut objects: Query<&mut Object>,
+    nav_mesh_lut: Res<NavMeshLUT>,
+    config: Res<CocoonConfig>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    let developing = cocoons
+        .iter()
+        .filter(|(_, c, _)| c.laid && !c.is_clue)
+        .count();
+    if developing == 0 {
+        return;
+    }
+    // Development slows down when the storage cannot feed every cocoon
+    let needed = developing as f32 * config.food_consumption * dt;
+    let fed_ratio = if needed > 0. {
+        take_from_storage(objects.iter_mut(), needed) / needed
+    } else {
+        1.
+    };
+
+    for (entity, mut cocoon, transform) in cocoons.iter_mut() {
+        if !cocoon.laid || cocoon.is_clue {
+            continue;
+        }
+        cocoon.development += dt * fed_ratio;
+        if cocoon.development < config.development_time {
+            continue;
+        }
+        let Some((nav_node, _)) = nav_mesh_lut.get_tile_entity(transform.translation().xy()) else {
+            continue;
+        };
+        if cocoon.infected {
+            AntSpawner::new(AntArchetype::Zombant, nav_node)
+                .job(Job::Thief)
+                .spawn(&mut commands);
+        } else {
+            AntSpawner::new(AntArchetype::Live, nav_node)
+                .job(Job::Food)
+                .spawn(&mut commands);
+        }
+        commands.entity(entity).remove_parent().despawn_recursive();
+    }
+}
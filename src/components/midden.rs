@@ -0,0 +1,126 @@
+use bevy::{prelude::*, utils::HashSet};
+use bevy_ecs_ldtk::LdtkEntity;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    components::{
+        ants::{
+            dead_ants::DeadAnt,
+            job::Job,
+            lod::{lod_ticking, OffScreen},
+            movement::AntMovement,
+        },
+        nursery::Carried,
+        pheromones::{concentrations::PheromoneConcentrations, PheromoneConfig, PheromoneKind},
+    },
+    resources::nav_mesh_lut::NavMeshLUT,
+    TILE_SIZE,
+};
+
+/// Distance from a [Midden] under which a carried corpse is dropped
+pub const MIDDEN_RADIUS: f32 = 3. * TILE_SIZE;
+
+/// Room where the undertakers pile up the dead ants, placed in LDtk
+#[derive(Debug, Default, Clone, Copy, Component, Reflect, LdtkEntity)]
+pub struct Midden {}
+
+/// Dead ant dropped in a [Midden], it does not smell anymore
+#[derive(Debug, Default, Clone, Copy, Component, Reflect)]
+pub struct Buried;
+
+pub fn update_midden_source(
+    middens: Query<&GlobalTransform, With<Midden>>,
+    mut nodes: Query<&mut PheromoneConcentrations>,
+    navmesh_lut: Res<NavMeshLUT>,
+    phcfg: Res<PheromoneConfig>,
+) {
+    for midden_transform in middens.iter() {
+        let Some((node, _)) = navmesh_lut.get_tile_entity(midden_transform.translation().xy())
+        else {
+            continue;
+        };
+        if let Ok(mut pheromones) = nodes.get_mut(node) {
+            pheromones.concentrations[PheromoneKind::Midden as usize] += phcfg.midden_source;
+        }
+    }
+}
+
+/// Undertakers pick up the dead ants they find, and drop them in the closest midden
+pub fn update_undertakers(
+    mut commands: Commands,
+    mut undertakers: Query<(
+        Entity,
+        &mut AntMovement,
+        &GlobalTransform,
+        Option<&OffScreen>,
+    )>,
+    dead_ants: Query<
+        (Entity, &GlobalTransform, Option<&Carried>),
+        (With<DeadAnt>, Without<Buried>),
+    >,
+    middens: Query<&GlobalTransform, With<Midden>>,
+    navmesh_lut: Res<NavMeshLUT>,
+) {
+    if middens.is_empty() {
+        return;
+    }
+    // The picked up corpses are only marked as carried once the commands are applied
+    let mut claimed = HashSet::new();
+    for (undertaker, mut ant_movement, undertaker_transform, off_screen) in undertakers.iter_mut() {
+        if !lod_ticking(off_screen) {
+            continue;
+        }
+        match ant_movement.goal.job {
+            Job::Undertaker => {
+                let Some((dead_ant, _, _)) =
+                    dead_ants.iter().find(|(dead_ant, transform, carried)| {
+                        carried.is_none()
+                            && !claimed.contains(dead_ant)
+                            && navmesh_lut.is_tile_node(
+                                transform.translation().xy(),
+                                ant_movement.current_node.0,
+                            )
+                    })
+                else {
+                    continue;
+                };
+                claimed.insert(dead_ant);
+                // The corpse is moved by the carrier instead of the physics
+                commands.entity(dead_ant).insert((
+                    Carried {
+                        carrier: undertaker,
+                    },
+                    RigidBody::KinematicPositionBased,
+                    // Drawn by the carrier instead
+                    Visibility::Hidden,
+                ));
+                ant_movement.goal.job = Job::Bury;
+                ant_movement.direction *= -1.;
+            }
+            Job::Bury => {
+                let pos = undertaker_transform.translation().xy();
+                let in_midden = middens
+                    .iter()
+                    .any(|midden| midden.translation().xy().distance(pos) < MIDDEN_RADIUS);
+                let carried = dead_ants
+                    .iter()
+                    .find(|(_, _, carried)| carried.is_some_and(|c| c.carrier == undertaker));
+                match carried {
+                    Some((dead_ant, _, _)) if in_midden => {
+                        commands.entity(dead_ant).remove::<Carried>().insert((
+                            Buried,
+                            RigidBody::Dynamic,
+                            Visibility::Inherited,
+                        ));
+                    }
+                    Some(_) => continue,
+                    // The corpse was lost on the way
+                    None => {}
+                }
+                ant_movement.goal.job = Job::Undertaker;
+                ant_movement.direction *= -1.;
+            }
+            _ => {}
+        }
+    }
+}
@@ -1,7 +1,9 @@
 pub mod ants;
 pub mod clues;
 pub mod cocoons;
+pub mod midden;
 pub mod nav_mesh;
+pub mod nursery;
 pub mod object;
 pub mod pheromones;
 pub mod player;
@@ -1,3 +1,5 @@
+use std::collections::VecDeque;
+
 use bevy::{prelude::*, utils::HashMap};
 use bevy_ecs_ldtk::prelude::*;
 use bevy_ecs_tilemap::tiles::TileStorage;
@@ -93,6 +95,41 @@ impl EdgeNeighbor {
         }
     }
 }
+/// Shortest path from `start` to `goal` on the nav mesh, excluding `start`
+///
+/// Nodes are weighted equally, `neighbors` gives the neighbors of a node.
+pub fn find_path(
+    start: Entity,
+    goal: Entity,
+    neighbors: impl Fn(Entity) -> Vec<Entity>,
+) -> Option<Vec<Entity>> {
+    if start == goal {
+        return Some(Vec::new());
+    }
+    let mut came_from = HashMap::new();
+    came_from.insert(start, start);
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        if node == goal {
+            let mut path = vec![goal];
+            let mut node = goal;
+            while came_from[&node] != start {
+                node = came_from[&node];
+                path.push(node);
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for neighbor in neighbors(node) {
+            if !came_from.contains_key(&neighbor) {
+                came_from.insert(neighbor, node);
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    None
+}
+
 pub fn spawn_nav_mesh(
     mut commands: Commands,
     mut level_events: EventReader<LevelEvent>,
@@ -562,6 +599,7 @@ pub fn spawn_nav_mesh(
             grid_entity: grid_entity_vec,
             grid_edges,
             grid_is_empty: grid_is_empty_vec,
+            grid_is_ground: grid_int.iter().map(|i| *i == TILE_INT_GROUND).collect(),
             grid_width: grid_width as usize,
             grid_height: grid_height as usize,
             tile_width: *tile_size as usize,
@@ -717,3 +755,91 @@ impl Index2d {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Neighbors on a `width` x `height` grid of nodes, ignoring the `walls`
+    fn grid_neighbors(
+        width: u32,
+        height: u32,
+        walls: &[u32],
+    ) -> impl Fn(Entity) -> Vec<Entity> + '_ {
+        move |node| {
+            let (x, y) = (node.index() % width, node.index() / width);
+            let mut neighbors = Vec::new();
+            if x > 0 {
+                neighbors.push(node.index() - 1);
+            }
+            if x + 1 < width {
+                neighbors.push(node.index() + 1);
+            }
+            if y > 0 {
+                neighbors.push(node.index() - width);
+            }
+            if y + 1 < height {
+                neighbors.push(node.index() + width);
+            }
+            neighbors
+                .into_iter()
+                .filter(|index| !walls.contains(index))
+                .map(Entity::from_raw)
+                .collect()
+        }
+    }
+
+    #[test]
+    fn path_to_self_is_empty() {
+        let node = Entity::from_raw(4);
+        assert_eq!(
+            find_path(node, node, grid_neighbors(3, 3, &[])),
+            Some(vec![])
+        );
+    }
+
+    #[test]
+    fn path_is_shortest_and_excludes_start() {
+        let path = find_path(
+            Entity::from_raw(0),
+            Entity::from_raw(2),
+            grid_neighbors(3, 1, &[]),
+        );
+        assert_eq!(path, Some(vec![Entity::from_raw(1), Entity::from_raw(2)]));
+
+        let path = find_path(
+            Entity::from_raw(0),
+            Entity::from_raw(8),
+            grid_neighbors(3, 3, &[]),
+        )
+        .unwrap();
+        assert_eq!(path.len(), 4);
+        assert_eq!(path.last(), Some(&Entity::from_raw(8)));
+    }
+
+    #[test]
+    fn path_goes_around_walls() {
+        // 0 1 2
+        // 3 # 5
+        // 6 7 8
+        let path = find_path(
+            Entity::from_raw(3),
+            Entity::from_raw(5),
+            grid_neighbors(3, 3, &[4]),
+        )
+        .unwrap();
+        assert_eq!(path.len(), 4);
+        assert!(!path.contains(&Entity::from_raw(4)));
+    }
+
+    #[test]
+    fn no_path_to_unreachable_goal() {
+        // 0 # 2
+        let path = find_path(
+            Entity::from_raw(0),
+            Entity::from_raw(2),
+            grid_neighbors(3, 1, &[1]),
+        );
+        assert_eq!(path, None);
+    }
+}
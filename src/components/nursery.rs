@@ -0,0 +1,301 @@
+use bevy::{prelude::*, utils::HashSet};
+use bevy_rapier2d::prelude::RigidBody;
+
+use crate::{
+    components::{
+        ants::{
+            job::Job,
+            lod::{lod_frames, lod_ticking, OffScreen},
+            movement::AntMovement,
+        },
+        cocoons::Cocoon,
+        pheromones::{concentrations::PheromoneConcentrations, PheromoneConfig, PheromoneKind},
+        player::Player,
+    },
+    resources::nav_mesh_lut::NavMeshLUT,
+    COCOON_ROOMS, TILE_SIZE,
+};
+
+/// Distance from the center of the nursery under which a carried cocoon is dropped
+pub const NURSERY_RADIUS: f32 = 3. * TILE_SIZE;
+
+/// Room where the nurses gather the cocoons
+#[derive(Debug, Clone, Resource, Reflect)]
+pub struct Nursery {
+    pub room: Option<u8>,
+    pub rooms: Vec<NurseryRoom>,
+    /// Seconds between two evaluations of the rooms
+    pub period: f32,
+    /// Suitability lost by a room when the player stands at its center
+    pub player_weight: f32,
+    /// Distance under which the player makes a room less suitable
+    pub player_radius: f32,
+    /// Suitability lost per unit of [PheromoneKind::DeadAnt] and [PheromoneKind::Zombant]
+    pub threat_weight: f32,
+    /// Suitability a room must gain over the current nursery to replace it
+    pub hysteresis: f32,
+    #[reflect(ignore)]
+    last_update: f32,
+}
+
+impl Default for Nursery {
+    fn default() -> Self {
+        Self {
+            room: None,
+            rooms: Vec::new(),
+            period: 2.,
+            player_weight: 1.,
+            player_radius: 20. * TILE_SIZE,
+            threat_weight: 0.05,
+            hysteresis: 0.2,
+            last_update: 0.,
+        }
+    }
+}
+
+impl Nursery {
+    pub fn current(&self) -> Option<&NurseryRoom> {
+        self.rooms.iter().find(|r| Some(r.room) == self.room)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Reflect)]
+pub struct NurseryRoom {
+    pub room: u8,
+    /// Average position of the cocoons initially placed in the room
+    pub center: Vec2,
+    pub nav_node: Entity,
+    pub suitability: f32,
+}
+
+/// Cocoon carried by an ant, following it until it is dropped
+#[derive(Debug, Clone, Copy, Component, Reflect)]
+pub struct Carried {
+    pub carrier: Entity,
+}
+
+/// Find the rooms where cocoons can be moved, the room with the most cocoons becomes the nursery
+pub fn init_nursery(
+    mut nursery: ResMut<Nursery>,
+    cocoons: Query<(&Cocoon, &GlobalTransform)>,
+    nav_mesh_lut: Res<NavMeshLUT>,
+) {
+    let mut most_cocoons = 0;
+    nursery.rooms.clear();
+    for room in COCOON_ROOMS {
+        let positions: Vec<Vec2> = cocoons
+            .iter()
+            .filter(|(cocoon, _)| cocoon.room == *room)
+            .map(|(_, transform)| transform.translation().xy())
+            .collect();
+        if positions.is_empty() {
+            continue;
+        }
+        let center = positions.iter().sum::<Vec2>() / positions.len() as f32;
+        let Some((nav_node, _)) = nav_mesh_lut.get_tile_entity(center) else {
+            warn!("The center of room {room} is not on the nav mesh");
+            continue;
+        };
+        nursery.rooms.push(NurseryRoom {
+            room: *room,
+            center,
+            nav_node,
+            suitability: 0.,
+        });
+        if positions.len() > most_cocoons {
+            most_cocoons = positions.len();
+            nursery.room = Some(*room);
+        }
+    }
+}
+
+/// Rate the rooms and move the nursery to the safest one
+pub fn update_nursery(
+    mut nursery: ResMut<Nursery>,
+    players: Query<&GlobalTransform, With<Player>>,
+    nodes: Query<&PheromoneConcentrations>,
+    time: Res<Time>,
+) {
+    let elapsed = time.elapsed_seconds();
+    if elapsed - nursery.last_update < nursery.period {
+        return;
+    }
+    nursery.last_update = elapsed;
+
+    let player_pos = players.get_single().ok().map(|p| p.translation().xy());
+    let Nursery {
+        player_weight,
+        player_radius,
+        threat_weight,
+        hysteresis,
+        ..
+    } = *nursery;
+    for room in nursery.rooms.iter_mut() {
+        let player_penalty = player_pos
+            .map(|pos| (1. - pos.distance(room.center) / player_radius).max(0.))
+            .unwrap_or_default();
+        let threat = nodes
+            .get(room.nav_node)
+            .map(|ph| {
+                ph.concentrations[PheromoneKind::DeadAnt as usize]
+                    + ph.concentrations[PheromoneKind::Zombant as usize]
+            })
+            .unwrap_or_default();
+        room.suitability = -player_weight * player_penalty - threat_weight * threat;
+    }
+
+    let current = nursery.current().map(|r| r.suitability);
+    let best = nursery
+        .rooms
+        .iter()
+        .max_by(|a, b| a.suitability.total_cmp(&b.suitability))
+        .copied();
+    if let Some(best) = best {
+        if current.map_or(true, |current| best.suitability > current + hysteresis) {
+            nursery.room = Some(best.room);
+        }
+    }
+}
+
+pub fn update_nursery_source(
+    nursery: Res<Nursery>,
+    mut nodes: Query<&mut PheromoneConcentrations>,
+    phcfg: Res<PheromoneConfig>,
+) {
+    let Some(room) = nursery.current() else {
+        return;
+    };
+    if let Ok(mut pheromones) = nodes.get_mut(room.nav_node) {
+        pheromones.concentrations[PheromoneKind::Nursery as usize] += phcfg.nursery_source;
+    }
+}
+
+/// Cocoons outside of the nursery and nurses carrying them lay brood pheromones
+pub fn update_brood_deposit(
+    nursery: Res<Nursery>,
+    cocoons: Query<(&Cocoon, &GlobalTransform), Without<Carried>>,
+    nurses: Query<(&AntMovement, Option<&OffScreen>)>,
+    mut nodes: Query<&mut PheromoneConcentrations>,
+    navmesh_lut: Res<NavMeshLUT>,
+    phcfg: Res<PheromoneConfig>,
+    time: Res<Time>,
+) {
+    for (cocoon, cocoon_transform) in cocoons.iter() {
+        if Some(cocoon.room) == nursery.room {
+            continue;
+        }
+        let Some((node, _)) = navmesh_lut.get_tile_entity(cocoon_transform.translation().xy())
+        else {
+            continue;
+        };
+        if let Ok(mut pheromones) = nodes.get_mut(node) {
+            pheromones.concentrations[PheromoneKind::Brood as usize] += phcfg.brood_deposit;
+        }
+    }
+    let dt = time.delta_seconds();
+    for (ant_movement, off_screen) in nurses.iter() {
+        if ant_movement.goal.job != Job::Relocate {
+            continue;
+        }
+        let Some(frames) = lod_frames(off_screen, dt) else {
+            continue;
+        };
+        let mut pheromones = nodes.get_mut(ant_movement.current_node.0).unwrap();
+        pheromones.concentrations[PheromoneKind::Brood as usize] += phcfg.brood_deposit * frames;
+    }
+}
+
+/// Nurses pick up the cocoons they find outside of the nursery, and drop them in it
+pub fn update_nurses(
+    mut commands: Commands,
+    nursery: Res<Nursery>,
+    mut nurses: Query<(
+        Entity,
+        &mut AntMovement,
+        &GlobalTransform,
+        Option<&OffScreen>,
+    )>,
+    mut cocoons: Query<(Entity, &mut Cocoon, &GlobalTransform, Option<&Carried>)>,
+    navmesh_lut: Res<NavMeshLUT>,
+) {
+    let Some(nursery_room) = nursery.current() else {
+        return;
+    };
+    // The picked up cocoons are only marked as carried once the commands are applied
+    let mut claimed = HashSet::new();
+    for (nurse, mut ant_movement, nurse_transform, off_screen) in nurses.iter_mut() {
+        if !lod_ticking(off_screen) {
+            continue;
+        }
+        match ant_movement.goal.job {
+            Job::Nurse => {
+                let Some((cocoon, _, _, _)) =
+                    cocoons.iter().find(|(entity, cocoon, transform, carried)| {
+                        carried.is_none()
+                            && !claimed.contains(entity)
+                            && cocoon.room != nursery_room.room
+                            && navmesh_lut.is_tile_node(
+                                transform.translation().xy(),
+                                ant_movement.current_node.0,
+                            )
+                    })
+                else {
+                    continue;
+                };
+                claimed.insert(cocoon);
+                commands.entity(cocoon).insert(Carried { carrier: nurse });
+                ant_movement.goal.job = Job::Relocate;
+                ant_movement.direction *= -1.;
+            }
+            Job::Relocate => {
+                let in_nursery = nurse_transform
+                    .translation()
+                    .xy()
+                    .distance(nursery_room.center)
+                    < NURSERY_RADIUS;
+                let carried = cocoons
+                    .iter_mut()
+                    .find(|(_, _, _, carried)| carried.is_some_and(|c| c.carrier == nurse));
+                match carried {
+                    Some((cocoon_entity, mut cocoon, _, _)) if in_nursery => {
+                        cocoon.room = nursery_room.room;
+                        commands.entity(cocoon_entity).remove::<Carried>();
+                    }
+                    Some(_) => continue,
+                    // The cocoon was lost on the way
+                    None => {}
+                }
+                ant_movement.goal.job = Job::Nurse;
+                ant_movement.direction *= -1.;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Carried cocoons and dead ants follow their carrier, and fall where it died
+pub fn update_carried(
+    mut commands: Commands,
+    mut carried: Query<(Entity, &Carried, &mut Transform, Option<&RigidBody>)>,
+    carriers: Query<&Transform, Without<Carried>>,
+) {
+    for (entity, carried, mut transform, rigid_body) in carried.iter_mut() {
+        if let Ok(carrier_transform) = carriers.get(carried.carrier) {
+            transform.translation.x = carrier_transform.translation.x;
+            transform.translation.y = carrier_transform.translation.y;
+        } else {
+            drop_carried(&mut commands, entity, rigid_body);
+        }
+    }
+}
+
+/// Drop a carried cocoon or dead ant where it is
+pub fn drop_carried(commands: &mut Commands, entity: Entity, rigid_body: Option<&RigidBody>) {
+    commands.entity(entity).remove::<Carried>();
+    // Dead ants are hidden while carried
+    if rigid_body.is_some() {
+        commands
+            .entity(entity)
+            .insert((RigidBody::Dynamic, Visibility::Inherited));
+    }
+}
@@ -8,6 +8,8 @@ pub struct Object {
     pub kind: PheromoneKind,
     pub quantity: Option<f32>,
     pub concentration: f32,
+    /// Touched by the Zomb'Ant Queen, ants carrying this food may get infected
+    pub tainted: bool,
 }
 
 impl Object {
@@ -16,6 +18,26 @@ impl Object {
     }
 }
 
+/// Take up to `amount` food from the [PheromoneKind::Storage] objects, returning the quantity taken
+pub fn take_from_storage<'a>(objects: impl Iterator<Item = Mut<'a, Object>>, amount: f32) -> f32 {
+    let mut taken = 0.;
+    for mut object in objects {
+        if taken >= amount {
+            break;
+        }
+        if object.kind != PheromoneKind::Storage {
+            continue;
+        }
+        let Some(quantity) = object.quantity else {
+            continue;
+        };
+        let take = quantity.min(amount - taken);
+        object.quantity = Some(quantity - take);
+        taken += take;
+    }
+    taken
+}
+
 #[derive(Bundle)]
 pub struct ObjectBundle {
     pub object: Object,
@@ -58,6 +80,7 @@ impl LdtkEntity for ObjectBundle {
             kind,
             quantity,
             concentration,
+            tainted: false,
         };
 
         Self { object, coords }
@@ -13,8 +13,13 @@ pub enum PheromoneKind {
     Zombqueen = 3,
     Zombant = 4,
     DeadAnt = 5,
+    Brood = 6,
+    Nursery = 7,
+    Queen = 8,
+    Alarm = 9,
+    Midden = 10,
 }
-pub const N_PHEROMONE_KINDS: usize = 6;
+pub const N_PHEROMONE_KINDS: usize = 11;
 
 #[derive(Resource, Reflect)]
 pub struct PheromoneConfig {
@@ -26,6 +31,12 @@ pub struct PheromoneConfig {
     pub dead_ant_deposit: f32,
     pub zombant_deposit: f32,
     pub zombqueen_source: f32,
+    pub brood_deposit: f32,
+    pub nursery_source: f32,
+    pub queen_source: f32,
+    /// Alarm emitted once by each explosion and each death
+    pub alarm_pulse: f32,
+    pub midden_source: f32,
 }
 
 impl Default for PheromoneConfig {
@@ -41,6 +52,11 @@ impl Default for PheromoneConfig {
             dead_ant_deposit: 1.0,
             zombant_deposit: 1.0,
             zombqueen_source: 40.0,
+            brood_deposit: 0.5,
+            nursery_source: 20.0,
+            queen_source: 20.0,
+            alarm_pulse: 20.0,
+            midden_source: 20.0,
         };
 
         config.color[Default as usize] = (Color::PURPLE, Color::FUCHSIA);
@@ -49,6 +65,11 @@ impl Default for PheromoneConfig {
         config.color[Zombqueen as usize] = (Color::MAROON, Color::CRIMSON);
         config.color[Zombant as usize] = (Color::BEIGE, Color::DARK_GRAY);
         config.color[DeadAnt as usize] = (Color::BLACK, Color::GRAY);
+        config.color[Brood as usize] = (Color::GOLD, Color::YELLOW);
+        config.color[Nursery as usize] = (Color::TEAL, Color::TURQUOISE);
+        config.color[Queen as usize] = (Color::ORANGE, Color::ORANGE_RED);
+        config.color[Alarm as usize] = (Color::RED, Color::SALMON);
+        config.color[Midden as usize] = (Color::OLIVE, Color::DARK_GREEN);
 
         config.diffusion_rate[Default as usize] = 0.01;
         config.diffusion_rate[Storage as usize] = 0.06;
@@ -66,6 +87,21 @@ impl Default for PheromoneConfig {
         config.diffusion_floor[Zombqueen as usize] = 0.0001;
         config.concentration_floor[Zombqueen as usize] = 0.0001;
 
+        config.evaporation_rate[Brood as usize] = 0.05;
+        config.diffusion_rate[Brood as usize] = 0.06;
+
+        config.evaporation_rate[Nursery as usize] = 0.01;
+        config.diffusion_rate[Nursery as usize] = 0.3;
+
+        config.evaporation_rate[Queen as usize] = 0.01;
+        config.diffusion_rate[Queen as usize] = 0.3;
+
+        config.evaporation_rate[Alarm as usize] = 0.2;
+        config.diffusion_rate[Alarm as usize] = 0.3;
+
+        config.evaporation_rate[Midden as usize] = 0.01;
+        config.diffusion_rate[Midden as usize] = 0.3;
+
         config
     }
 }
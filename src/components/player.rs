@@ -7,22 +7,23 @@ use bevy_rapier2d::prelude::*;
 use crate::{
     components::{
         ants::{
-            dead_ants::DeadAntBundle,
+            dead_ants::kill_ant,
             movement::{position::AntPositionKind, AntMovement},
-            AntStyle,
         },
         clues::Clue,
         nav_mesh::NavNode,
     },
     render::player_animation::PlayerAnimationBundle,
     resources::clues::ClueEvent,
-    COLLISION_GROUP_ANTS, COLLISION_GROUP_CLUE, COLLISION_GROUP_DEAD_ANTS, COLLISION_GROUP_PLAYER,
-    COLLISION_GROUP_PLAYER_SENSOR, COLLISION_GROUP_WALLS, PLAYER_SIZE, RENDERLAYER_PLAYER,
+    AppState, GameOverCause, COLLISION_GROUP_ANTS, COLLISION_GROUP_CLUE, COLLISION_GROUP_DEAD_ANTS,
+    COLLISION_GROUP_PLAYER, COLLISION_GROUP_PLAYER_SENSOR, COLLISION_GROUP_WALLS, PLAYER_SIZE,
+    RENDERLAYER_PLAYER,
 };
 
 #[derive(Bundle)]
 pub struct PlayerBundle {
     pub player: Player,
+    pub health: PlayerHealth,
     pub animation: PlayerAnimationBundle,
     pub controller: KinematicCharacterController,
     pub collider: Collider,
@@ -46,6 +47,7 @@ impl LdtkEntity for PlayerBundle {
             collider: Collider::cuboid(PLAYER_SIZE.x / 2. / 2., PLAYER_SIZE.y / 2. - 2.),
             collider_mass: ColliderMassProperties::Density(1.),
             player: Default::default(),
+            health: Default::default(),
             controller: KinematicCharacterController {
                 min_slope_slide_angle: PI / 5.,
                 filter_groups: Some(CollisionGroups::new(
@@ -71,8 +73,8 @@ pub fn update_player_sensor(
     player_sensors: Query<(&PlayerWallSensor, &CollidingEntities)>,
     mut players: Query<&mut Player>,
     nav_nodes: Query<&NavNode>,
-    ants: Query<(&AntMovement, &Transform, &Parent, &AntStyle)>,
-    clues: Query<(Entity, &Parent), With<Clue>>,
+    ants: Query<&AntMovement>,
+    clues: Query<(Entity, &Clue, &Parent)>,
     mut clue_events: EventWriter<ClueEvent>,
 ) {
     for (sensor, colliding_entities) in player_sensors.iter() {
@@ -87,18 +89,11 @@ pub fn update_player_sensor(
         player.on_wall.clear();
         for colliding_entity in colliding_entities.iter() {
             // Collision with ant
-            if let Ok((ant_movement, ant_transform, ant_parent, ant_style)) =
-                ants.get(colliding_entity)
-            {
+            if let Ok(ant_movement) = ants.get(colliding_entity) {
                 if !matches!(ant_movement.position_kind, AntPositionKind::Background)
                     && !player.is_crouching
                 {
-                    // Spawn a dead ant
-                    commands
-                        .spawn(DeadAntBundle::new(*ant_transform, *ant_style))
-                        .set_parent(ant_parent.get());
-                    // Despawn the alive ant
-                    commands.entity(colliding_entity).remove_parent().despawn();
+                    kill_ant(&mut commands, colliding_entity);
                 }
             // Collision with wall
             } else if let Ok(nav_node) = nav_nodes.get(colliding_entity) {
@@ -126,11 +121,11 @@ pub fn update_player_sensor(
                     _ => (),
                 }
             // Collision with a clue
-            } else if let Ok((entity, parent)) = clues.get(colliding_entity) {
+            } else if let Ok((entity, clue, parent)) = clues.get(colliding_entity) {
                 // TODO: SFX
                 commands.entity(parent.get()).remove_children(&[entity]);
                 commands.entity(entity).despawn();
-                clue_events.send(ClueEvent::Found);
+                clue_events.send(ClueEvent::Found(clue.queen));
             }
         }
     }
@@ -148,6 +143,19 @@ pub struct Player {
     pub is_crouching: bool,
 }
 
+/// Health of the player, bitten by hostile soldiers
+#[derive(Debug, Copy, Clone, Reflect, Component)]
+pub struct PlayerHealth {
+    /// Between 0 (dead) and 1
+    pub health: f32,
+}
+
+impl Default for PlayerHealth {
+    fn default() -> Self {
+        Self { health: 1. }
+    }
+}
+
 #[derive(Debug, Copy, Clone, Reflect, Component)]
 pub struct PlayerWallSensor {
     player: Entity,
@@ -179,3 +187,18 @@ pub fn spawn_player_sensor(
             .set_parent(entity);
     }
 }
+
+/// The game is lost when the player dies
+pub fn update_player_health(
+    players: Query<&PlayerHealth, Changed<PlayerHealth>>,
+    mut game_over_cause: ResMut<GameOverCause>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if players
+        .iter()
+        .any(|player_health| player_health.health <= 0.)
+    {
+        *game_over_cause = GameOverCause::PlayerKilled;
+        next_state.set(AppState::GameOver);
+    }
+}
@@ -14,21 +14,49 @@ use bevy_rapier2d::prelude::*;
 
 use components::{
     ants::{
-        dead_ants::update_dead_ants_deposit,
-        movement::position::{update_ant_position, update_ant_position_kinds},
+        alarm::{update_alarm_response, update_alarm_sources, AlarmConfig},
+        boss::{update_queen_boss, BossConfig},
+        colony_queen::{
+            spawn_colony_queen, update_colony_queen, update_colony_queen_source, ColonyQueenConfig,
+            ColonyQueenSpawnPoint,
+        },
+        crowd::{update_crowd_avoidance, CrowdConfig},
+        dead_ants::{
+            replace_dying_ants, update_dead_ants_decomposition, update_dead_ants_deposit,
+            DecompositionConfig,
+        },
+        evasion::{update_queen_evasion, EvasionConfig},
+        infection::{update_infection, update_tainted_objects, InfectionConfig},
+        life::{update_ant_life, LifeConfig},
+        lod::{tick_offscreen_ants, update_ant_lod, update_offscreen_ants, LodConfig},
+        movement::{
+            falling::{update_falling_ants, FallConfig},
+            nav_graph::{
+                update_ant_collision_groups, update_ant_position_on_nav_graph, LocomotionMode,
+            },
+            position::{update_ant_position, update_ant_position_kinds},
+            steering::{update_ant_steering, SteeringConfig},
+        },
+        soldiers::{update_colony_hostility, update_soldiers, ColonyHostility, SoldierConfig},
+        spawner::{spawn_placed_ants, AntSpawnPoint},
         zombants::{
-            spawn_zombant_queen, update_zombants_deposit, update_zombqueen_source,
-            ZombAntQueenSpawnPoint,
+            spawn_zombant_queen, update_zombants_deposit, update_zombqueen_brood,
+            update_zombqueen_source, ZombAntQueenConfig, ZombAntQueenSpawnPoint,
         },
     },
     ants::{
         goal::{update_ant_goal, update_metrics, Metrics},
-        movement::direction::update_ant_direction,
+        movement::direction::{update_ant_direction, SensingConfig},
         *,
     },
     clues::place_clues,
-    cocoons::CocoonBundle,
+    cocoons::{update_cocoons, CocoonBundle, CocoonConfig},
+    midden::{update_midden_source, update_undertakers, Midden},
     nav_mesh::*,
+    nursery::{
+        init_nursery, update_brood_deposit, update_carried, update_nursery, update_nursery_source,
+        update_nurses, Nursery,
+    },
     object::ObjectBundle,
     pheromones::{
         concentrations::diffuse_pheromones, concentrations::init_pheromones,
@@ -47,9 +75,11 @@ use render::{
 };
 use resources::{
     clues::{clues_receive_events, ClueEvent, Clues},
+    colony::{load_colony_config, spawn_colony, ColonyConfig},
+    job_allocation::{allocate_jobs, JobAllocation},
     nav_mesh_lut::NavMeshLUT,
 };
-use ui::win::display_win;
+use ui::win::{display_game_over, display_win};
 
 pub struct GamePlugin;
 
@@ -58,18 +88,42 @@ impl Plugin for GamePlugin {
         app.register_ldtk_entity::<PlayerBundle>("Player")
             .register_ldtk_entity::<CocoonBundle>("Shedding")
             .register_ldtk_entity::<ObjectBundle>("Source")
-            // .register_ldtk_entity::<AntBundle>("Ant")
+            .register_ldtk_entity::<AntSpawnPoint>("Ant")
             .register_ldtk_entity::<ZombAntQueenSpawnPoint>("Zombant_Queen_Spawn_Point")
+            .register_ldtk_entity::<ColonyQueenSpawnPoint>("Queen_Spawn_Point")
+            .register_ldtk_entity::<Midden>("Midden")
             .register_ldtk_int_cell::<TileGroundBundle>(TILE_INT_GROUND)
             .register_ldtk_int_cell::<TileEmptyUndergroundBundle>(TILE_INT_EMPTY)
             .register_ldtk_int_cell::<TileEmptyOvergroundBundle>(TILE_INT_OVERGROUND)
             .register_type::<components::nav_mesh::NavNode>()
             .register_type::<Clues>()
+            .register_type::<components::ants::caste::Caste>()
             .insert_resource(AssetMetaCheck::Never)
             .init_resource::<NavMeshLUT>()
             .add_event::<ClueEvent>()
             .init_resource::<PheromoneConfig>()
             .init_resource::<Metrics>()
+            .init_resource::<ColonyConfig>()
+            .init_resource::<JobAllocation>()
+            .init_resource::<Nursery>()
+            .init_resource::<CocoonConfig>()
+            .init_resource::<ColonyQueenConfig>()
+            .init_resource::<LifeConfig>()
+            .init_resource::<InfectionConfig>()
+            .init_resource::<ZombAntQueenConfig>()
+            .init_resource::<EvasionConfig>()
+            .init_resource::<BossConfig>()
+            .init_resource::<SoldierConfig>()
+            .init_resource::<ColonyHostility>()
+            .init_resource::<AlarmConfig>()
+            .init_resource::<DecompositionConfig>()
+            .init_resource::<SensingConfig>()
+            .init_resource::<SteeringConfig>()
+            .init_resource::<CrowdConfig>()
+            .init_resource::<LocomotionMode>()
+            .init_resource::<FallConfig>()
+            .init_resource::<LodConfig>()
+            .init_resource::<GameOverCause>()
             .add_plugins((
                 DefaultPlugins.set(ImagePlugin::default_nearest()), // prevents blurry sprites? (TODO: test)
                 LdtkPlugin,
@@ -95,9 +149,19 @@ impl Plugin for GamePlugin {
                 (
                     // One-shot systems that need nav nodes
                     spawn_player_sensor,
-                    spawn_zombant_queen,
-                    place_clues,
-                    (init_pheromones, apply_deferred, init_sources).chain(),
+                    spawn_placed_ants,
+                    // Clues are about the spawned queens
+                    (spawn_zombant_queen, apply_deferred, place_clues).chain(),
+                    (init_nursery, spawn_colony_queen).chain(),
+                    (
+                        init_pheromones,
+                        apply_deferred,
+                        init_sources,
+                        apply_deferred,
+                        load_colony_config,
+                        spawn_colony,
+                    )
+                        .chain(),
                 ),
             )
             .add_systems(
@@ -107,31 +171,82 @@ impl Plugin for GamePlugin {
             .add_systems(
                 Update,
                 (
-                    debug_pheromones.run_if(toggle_on_key(KeyCode::H)),
-                    pause_if_not_focused,
-                    update_player_sensor,
-                    clues_receive_events,
-                    ant_explosion_collision,
+                    // Ants and dead ants are only despawned here, and the off-screen ants need
+                    // their tick before any other ant system
                     (
-                        update_ant_position_kinds,
-                        // assert_ants, // TODO: disable in release?
-                        update_ant_direction,
-                        // update_ant_direction_randomly,
-                        update_ant_position,
-                        update_dead_ants_deposit,
-                        update_zombants_deposit,
-                        update_zombqueen_source,
-                        update_ant_goal,
-                        update_metrics,
-                        diffuse_pheromones,
-                        apply_sources,
-                        compute_gradients,
-                    )
-                        .chain(),
+                        tick_offscreen_ants,
+                        replace_dying_ants,
+                        update_dead_ants_decomposition,
+                    ),
+                    (
+                        debug_pheromones.run_if(toggle_on_key(KeyCode::H)),
+                        pause_if_not_focused,
+                        update_player_sensor,
+                        clues_receive_events,
+                        ant_explosion_collision,
+                        update_queen_boss,
+                        update_colony_hostility,
+                        update_player_health,
+                        update_ant_life,
+                        update_ant_carried_items,
+                        update_ant_collision_groups,
+                        update_ant_lod,
+                        (update_tainted_objects, update_infection).chain(),
+                        (
+                            update_ant_position_kinds
+                                .run_if(resource_equals(LocomotionMode::Physics)),
+                            // assert_ants, // TODO: disable in release?
+                            update_ant_direction,
+                            update_queen_evasion,
+                            update_alarm_response,
+                            update_soldiers,
+                            // update_ant_direction_randomly,
+                            update_crowd_avoidance,
+                            update_ant_steering,
+                            (
+                                update_ant_position
+                                    .run_if(resource_equals(LocomotionMode::Physics)),
+                                update_ant_position_on_nav_graph
+                                    .run_if(resource_equals(LocomotionMode::NavGraph)),
+                                update_falling_ants,
+                                update_offscreen_ants,
+                            ),
+                            update_dead_ants_deposit,
+                            update_alarm_sources,
+                            update_zombants_deposit,
+                            update_zombqueen_source,
+                            update_zombqueen_brood,
+                            (
+                                update_nursery,
+                                update_nursery_source,
+                                update_brood_deposit,
+                                update_nurses,
+                                update_midden_source,
+                                update_undertakers,
+                                update_carried,
+                                update_cocoons,
+                                update_colony_queen,
+                                update_colony_queen_source,
+                            )
+                                .chain(),
+                            update_ant_goal,
+                            update_metrics,
+                            allocate_jobs,
+                            diffuse_pheromones,
+                            apply_sources,
+                            compute_gradients,
+                        )
+                            .chain(),
+                    ),
                 )
+                    .chain()
                     .run_if(in_state(AppState::Playing)),
             )
-            .add_systems(Update, (display_win).run_if(in_state(AppState::Win)));
+            .add_systems(Update, (display_win).run_if(in_state(AppState::Win)))
+            .add_systems(
+                Update,
+                (display_game_over).run_if(in_state(AppState::GameOver)),
+            );
     }
 }
 
@@ -143,6 +258,15 @@ pub enum AppState {
     ProcessingOthers,
     Playing,
     Win,
+    GameOver,
+}
+
+/// What ended the game in [AppState::GameOver]
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Resource)]
+pub enum GameOverCause {
+    #[default]
+    ColonyQueenKilled,
+    PlayerKilled,
 }
 
 #[derive(AssetCollection, Resource)]
@@ -171,6 +295,8 @@ pub fn start_playing(mut next_state: ResMut<NextState<AppState>>) {
 pub const PIXELS_PER_METER: f32 = 16.;
 pub const PLAYER_SIZE: Vec2 = Vec2::new(32., 32.);
 pub const ANT_SIZE: Vec2 = Vec2::new(16., 16.);
+/// Default speed of the ants, in pixels per second
+pub const ANT_SPEED: f32 = 40.;
 /// Vertical and horizontal edges will have their [NavNode] placed at `tile_size * WALL_Z_FACTOR / 2.` in Z
 pub const WALL_Z_FACTOR: f32 = 1.;
 /// Number of pixels the ants should be clipping in a wall when moving on it
@@ -151,8 +151,12 @@ impl Material2d for AntMaterial {
                 VertexFormat::Float32x4,
                 // phase
                 VertexFormat::Float32,
-                // padding
-                VertexFormat::Float32x3,
+                // halo
+                VertexFormat::Float32,
+                // decay
+                VertexFormat::Float32,
+                // carried
+                VertexFormat::Float32,
             ],
         );
         // The mesh pipeline uses location 0 through 4
@@ -192,7 +196,10 @@ pub struct AntMaterialInstance {
     color_primary: Vec4,
     color_secondary: Vec4,
     animation_phase: f32,
-    _padding: Vec3,
+    halo: f32,
+    decay: f32,
+    /// [CarriedItem](crate::components::ants::CarriedItem) as a float
+    carried: f32,
 }
 
 impl Default for AntMaterialInstance {
@@ -201,7 +208,9 @@ impl Default for AntMaterialInstance {
             color_primary: Color::WHITE.into(),
             color_secondary: Color::PURPLE.into(),
             animation_phase: 0.,
-            _padding: Default::default(),
+            halo: 0.,
+            decay: 0.,
+            carried: 0.,
         }
     }
 }
@@ -221,7 +230,9 @@ impl ExtractComponent for RenderAnt {
                 color_primary: ant_style.color_primary.into(),
                 color_secondary: ant_style.color_secondary.into(),
                 animation_phase: ant_style.animation_phase,
-                _padding: Vec3::ZERO,
+                halo: ant_style.halo,
+                decay: ant_style.decay,
+                carried: ant_style.carried as u32 as f32,
             },
             index: u64::MAX,
         })
@@ -262,41 +273,43 @@ pub fn prepare_ant_material(
 }
 
 pub fn extract_ants_render_transform(
-    mut ants: Extract<Query<(Entity, &AntMovement, &ViewVisibility)>>,
+    mut ants: Extract<Query<(Entity, &AntMovement, &AntStyle, &ViewVisibility)>>,
     mut meshes: ResMut<RenderMesh2dInstances>,
 ) {
     let meshes = meshes.deref_mut().deref_mut();
-    for (entity, ant, view_visibility) in ants.iter_mut() {
+    for (entity, ant, ant_style, view_visibility) in ants.iter_mut() {
         if !view_visibility.get() {
             continue;
         }
         let mesh2d = &mut meshes.get_mut(&entity).unwrap().transforms;
-        mesh2d.transform.matrix3 = match ant.position_kind {
+        let orientation = match ant.position_kind {
             AntPositionKind::Background => {
-                let angle = ant.direction.y.atan2(ant.direction.x);
-                Mat3A::from_angle(angle - PI / 2.).into()
+                let angle = ant.heading.y.atan2(ant.heading.x);
+                Mat3A::from_angle(angle - PI / 2.)
             }
             AntPositionKind::VerticalWall { is_left_side } => {
                 let mut scale = Vec2::ONE;
-                if ant.direction.y < 0. {
+                if ant.heading.y < 0. {
                     scale.y = -1.;
                 }
                 if !is_left_side {
                     scale.x = -1.;
                 }
-                (Mat3A::from_scale(scale) * Mat3A::from_angle(-PI / 2.)).into()
+                Mat3A::from_scale(scale) * Mat3A::from_angle(-PI / 2.)
             }
             AntPositionKind::HorizontalWall { is_up_side } => {
                 let mut scale = Vec2::ONE;
-                if ant.direction.x > 0. {
+                if ant.heading.x > 0. {
                     scale.x = -1.;
                 }
                 if is_up_side {
                     scale.y = -1.;
                 }
-                Mat3A::from_scale(scale).into()
+                Mat3A::from_scale(scale)
             }
         };
+        mesh2d.transform.matrix3 =
+            (orientation * Mat3A::from_scale(Vec2::splat(ant_style.scale))).into();
     }
 }
 
@@ -1,26 +1,52 @@
 use bevy::prelude::*;
-use rand::{rngs::ThreadRng, thread_rng, Rng};
+use rand::{rngs::ThreadRng, seq::SliceRandom, thread_rng, Rng};
 
 use crate::components::ants::{AntColorKind, AntStyle};
 
-#[derive(Debug, Clone, Copy, Resource, Reflect)]
+/// Number of clues revealing the whole disguise of a queen
+const DISGUISE_CLUES: usize = 2;
+
+#[derive(Debug, Clone, Resource, Reflect)]
 pub struct Clues {
-    pub z0_primary_color: bool,
-    pub z0_secondary_color: bool,
+    /// One clue chain per Zomb'Ant Queen alive
+    pub queens: Vec<QueenClues>,
     pub pheromone_view_charges: usize,
     // FIXME: separate into UI resource
     pub ui_text: Entity,
     pub ui_container: Entity,
+    pub ui_queens_text: Entity,
+}
+
+/// Disguise of a Zomb'Ant Queen revealed so far
+#[derive(Debug, Clone, Copy, Reflect)]
+pub struct QueenClues {
+    pub queen: Entity,
+    pub z0_primary_color: bool,
+    pub z0_secondary_color: bool,
+    // FIXME: separate into UI resource
     pub ui_ant_clue: Entity,
     pub ant_clue: Entity,
 }
 
-impl Clues {
-    pub fn reveal_next(&mut self, rng: &mut ThreadRng, ant_styles: &mut Query<&mut AntStyle>) {
+impl QueenClues {
+    pub fn new(queen: Entity, ui_ant_clue: Entity, ant_clue: Entity) -> Self {
+        Self {
+            queen,
+            z0_primary_color: false,
+            z0_secondary_color: false,
+            ui_ant_clue,
+            ant_clue,
+        }
+    }
+
+    /// Number of clues found about this queen
+    pub fn revealed(&self) -> usize {
+        self.z0_primary_color as usize + self.z0_secondary_color as usize
+    }
+
+    fn reveal_next(&mut self, rng: &mut ThreadRng, ant_styles: &mut Query<&mut AntStyle>) {
         match (self.z0_primary_color, self.z0_secondary_color) {
-            (true, true) => {
-                self.pheromone_view_charges += 1;
-            }
+            (true, true) => {}
             (true, false) => {
                 self.reveal_secondary_color(rng, ant_styles);
             }
@@ -61,9 +87,38 @@ impl Clues {
     }
 }
 
+impl Clues {
+    /// Reveal the disguise of `queen`, or give a pheromone view charge once she is known or dead
+    pub fn reveal_next(
+        &mut self,
+        queen: Entity,
+        rng: &mut ThreadRng,
+        ant_styles: &mut Query<&mut AntStyle>,
+    ) {
+        match self
+            .queens
+            .iter_mut()
+            .find(|queen_clues| queen_clues.queen == queen)
+        {
+            Some(queen_clues) if queen_clues.revealed() < DISGUISE_CLUES => {
+                queen_clues.reveal_next(rng, ant_styles);
+            }
+            _ => self.pheromone_view_charges += 1,
+        }
+    }
+
+    /// Whether at least one clue was found about a queen still alive
+    pub fn any_revealed(&self) -> bool {
+        self.queens
+            .iter()
+            .any(|queen_clues| queen_clues.revealed() > 0)
+    }
+}
+
 #[derive(Event)]
 pub enum ClueEvent {
-    Found,
+    /// A clue about the given Zomb'Ant Queen was found
+    Found(Entity),
 }
 
 pub fn clues_receive_events(
@@ -73,11 +128,16 @@ pub fn clues_receive_events(
 ) {
     for event in events.read() {
         match event {
-            ClueEvent::Found => clues.reveal_next(&mut thread_rng(), &mut ant_styles),
+            ClueEvent::Found(queen) => {
+                clues.reveal_next(*queen, &mut thread_rng(), &mut ant_styles)
+            }
         }
     }
 }
 
-pub fn found_clue(mut event: EventWriter<ClueEvent>) {
-    event.send(ClueEvent::Found);
+/// Find a clue about a random queen
+pub fn found_clue(mut event: EventWriter<ClueEvent>, clues: Res<Clues>) {
+    if let Some(queen_clues) = clues.queens.choose(&mut thread_rng()) {
+        event.send(ClueEvent::Found(queen_clues.queen));
+    }
 }
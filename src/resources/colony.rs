@@ -0,0 +1,325 @@
+use std::str::FromStr;
+
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use rand::{rngs::ThreadRng, seq::SliceRandom, thread_rng, Rng};
+
+use crate::{
+    components::{
+        ants::{
+            caste::Caste,
+            job::Job,
+            spawner::{AntArchetype, AntSpawner},
+        },
+        cocoons::Cocoon,
+        nav_mesh::NavNode,
+        object::Object,
+        pheromones::PheromoneKind,
+    },
+    ANT_SPEED, TILE_SIZE,
+};
+
+/// Name of the LDtk level field overriding the default [ColonyConfig]
+///
+/// It is an array of strings, each one describing a [ColonyPopulation], e.g.
+/// `archetype=live caste=major job=food count=100 region=storage speed=35..45 scale=0.9..1.1`
+pub const COLONY_LEVEL_FIELD: &str = "Colony";
+/// Distance from the center of a [SpawnRegion] within which ants can be spawned
+pub const SPAWN_REGION_RADIUS: f32 = 4. * TILE_SIZE;
+
+/// Initial population of the level
+#[derive(Debug, Clone, Resource, Reflect)]
+pub struct ColonyConfig {
+    pub populations: Vec<ColonyPopulation>,
+}
+
+impl Default for ColonyConfig {
+    fn default() -> Self {
+        Self {
+            populations: vec![
+                ColonyPopulation {
+                    archetype: AntArchetype::Live,
+                    job: Job::Food,
+                    count: 100,
+                    ..default()
+                },
+                ColonyPopulation {
+                    archetype: AntArchetype::Live,
+                    caste: Caste::Soldier,
+                    job: Job::Patrol,
+                    count: 10,
+                    ..default()
+                },
+                ColonyPopulation {
+                    archetype: AntArchetype::Zombant,
+                    job: Job::Thief,
+                    count: 20,
+                    ..default()
+                },
+            ],
+        }
+    }
+}
+
+/// A group of ants sharing the same archetype, caste, job and spawn region
+///
+/// `speed` and `scale` are further multiplied by the factors of the [Caste]
+#[derive(Debug, Clone, Copy, Reflect)]
+pub struct ColonyPopulation {
+    pub archetype: AntArchetype,
+    pub caste: Caste,
+    pub job: Job,
+    pub count: usize,
+    pub region: SpawnRegion,
+    pub speed: ValueRange,
+    pub scale: ValueRange,
+}
+
+impl Default for ColonyPopulation {
+    fn default() -> Self {
+        Self {
+            archetype: AntArchetype::Live,
+            caste: Caste::MinorWorker,
+            job: Job::Wander,
+            count: 0,
+            region: SpawnRegion::Anywhere,
+            speed: ValueRange::constant(ANT_SPEED),
+            scale: ValueRange::constant(1.),
+        }
+    }
+}
+
+impl FromStr for ColonyPopulation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut population = Self::default();
+        for token in s.split_whitespace() {
+            let Some((key, value)) = token.split_once('=') else {
+                return Err(format!("expected `key=value`, found `{token}`"));
+            };
+            match key {
+                "archetype" => population.archetype = value.parse()?,
+                "caste" => population.caste = value.parse()?,
+                "job" => population.job = value.parse()?,
+                "count" => {
+                    population.count = value
+                        .parse()
+                        .map_err(|_| format!("invalid count `{value}`"))?
+                }
+                "region" => population.region = value.parse()?,
+                "speed" => population.speed = value.parse()?,
+                "scale" => population.scale = value.parse()?,
+                _ => return Err(format!("unknown key `{key}`")),
+            }
+        }
+        Ok(population)
+    }
+}
+
+/// Where the ants of a [ColonyPopulation] are spawned
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum SpawnRegion {
+    /// Any node of the nav mesh
+    #[default]
+    Anywhere,
+    /// Around the cocoons of a room
+    Room(u8),
+    /// Around the storage sources
+    NearStorage,
+    /// On the ground outside of the nest
+    Surface,
+}
+
+impl FromStr for SpawnRegion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "anywhere" => Ok(Self::Anywhere),
+            "storage" => Ok(Self::NearStorage),
+            "surface" => Ok(Self::Surface),
+            _ => s
+                .strip_prefix("room")
+                .and_then(|room| room.parse().ok())
+                .map(Self::Room)
+                .ok_or_else(|| format!("unknown region `{s}`")),
+        }
+    }
+}
+
+/// Uniform distribution between `min` and `max`
+#[derive(Debug, Clone, Copy, Reflect)]
+pub struct ValueRange {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl ValueRange {
+    pub fn constant(value: f32) -> Self {
+        Self {
+            min: value,
+            max: value,
+        }
+    }
+
+    pub fn sample(&self, rng: &mut ThreadRng) -> f32 {
+        if self.min < self.max {
+            rng.gen_range(self.min..self.max)
+        } else {
+            self.min
+        }
+    }
+}
+
+impl FromStr for ValueRange {
+    type Err = String;
+
+    /// Parses either `value` or `min..max`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |value: &str| {
+            value
+                .parse::<f32>()
+                .map_err(|_| format!("invalid number `{value}`"))
+        };
+        match s.split_once("..") {
+            Some((min, max)) => Ok(Self {
+                min: parse(min)?,
+                max: parse(max)?,
+            }),
+            None => parse(s).map(Self::constant),
+        }
+    }
+}
+
+/// Override the default [ColonyConfig] with the fields of the current LDtk level, if any
+pub fn load_colony_config(
+    mut colony_config: ResMut<ColonyConfig>,
+    levels: Query<&LevelIid>,
+    ldtk_projects: Query<&Handle<LdtkProject>>,
+    ldtk_project_assets: Res<Assets<LdtkProject>>,
+) {
+    let (Ok(level_iid), Ok(ldtk_project)) = (levels.get_single(), ldtk_projects.get_single())
+    else {
+        return;
+    };
+    let Some(level) = ldtk_project_assets
+        .get(ldtk_project)
+        .and_then(|project| {
+            project
+                .as_standalone()
+                .get_loaded_level_by_iid(&level_iid.to_string())
+        })
+        .map(|level| level.raw())
+    else {
+        return;
+    };
+    // Levels without the field keep the default colony
+    let Ok(populations) = level.get_maybe_strings_field(COLONY_LEVEL_FIELD) else {
+        return;
+    };
+    colony_config.populations = populations
+        .iter()
+        .flatten()
+        .filter_map(|population| match population.parse::<ColonyPopulation>() {
+            Ok(population) => Some(population),
+            Err(err) => {
+                warn!("Ignoring colony population \"{population}\": {err}");
+                None
+            }
+        })
+        .collect();
+}
+
+/// Spawn the initial population described by [ColonyConfig]
+pub fn spawn_colony(
+    mut commands: Commands,
+    colony_config: Res<ColonyConfig>,
+    nav_nodes: Query<(Entity, &NavNode, &GlobalTransform)>,
+    cocoons: Query<(&Cocoon, &GlobalTransform)>,
+    objects: Query<(&Object, &GlobalTransform)>,
+) {
+    let mut rng = thread_rng();
+    for population in colony_config.populations.iter() {
+        let centers = match population.region {
+            SpawnRegion::Anywhere | SpawnRegion::Surface => Vec::new(),
+            SpawnRegion::Room(room) => cocoons
+                .iter()
+                .filter(|(cocoon, _)| cocoon.room == room)
+                .map(|(_, transform)| transform.translation().xy())
+                .collect(),
+            SpawnRegion::NearStorage => objects
+                .iter()
+                .filter(|(object, _)| object.kind == PheromoneKind::Storage)
+                .map(|(_, transform)| transform.translation().xy())
+                .collect(),
+        };
+        let candidates: Vec<Entity> = nav_nodes
+            .iter()
+            .filter(|(_, nav_node, transform)| match population.region {
+                SpawnRegion::Anywhere => true,
+                SpawnRegion::Surface => {
+                    matches!(nav_node, NavNode::HorizontalEdge { back: None, .. })
+                }
+                SpawnRegion::Room(_) | SpawnRegion::NearStorage => centers.iter().any(|center| {
+                    center.distance(transform.translation().xy()) < SPAWN_REGION_RADIUS
+                }),
+            })
+            .map(|(entity, _, _)| entity)
+            .collect();
+        if candidates.is_empty() {
+            warn!("No nav node in {:?} to spawn ants", population.region);
+            continue;
+        }
+        for _ in 0..population.count {
+            let nav_node = *candidates.choose(&mut rng).unwrap();
+            AntSpawner::new(population.archetype, nav_node)
+                .caste(population.caste)
+                .job(population.job)
+                .speed(population.speed.sample(&mut rng))
+                .scale(population.scale.sample(&mut rng))
+                .spawn(&mut commands);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_population() {
+        let population: ColonyPopulation =
+            "archetype=zombant caste=soldier job=patrol count=12 region=room2 speed=35..45 scale=0.9"
+                .parse()
+                .unwrap();
+        assert_eq!(population.archetype, AntArchetype::Zombant);
+        assert_eq!(population.caste, Caste::Soldier);
+        assert_eq!(population.job, Job::Patrol);
+        assert_eq!(population.count, 12);
+        assert_eq!(population.region, SpawnRegion::Room(2));
+        assert_eq!((population.speed.min, population.speed.max), (35., 45.));
+        assert_eq!((population.scale.min, population.scale.max), (0.9, 0.9));
+    }
+
+    #[test]
+    fn parse_population_defaults() {
+        let population: ColonyPopulation = "count=3".parse().unwrap();
+        assert_eq!(population.archetype, AntArchetype::Live);
+        assert_eq!(population.caste, Caste::MinorWorker);
+        assert_eq!(population.job, Job::Wander);
+        assert_eq!(population.count, 3);
+        assert_eq!(population.region, SpawnRegion::Anywhere);
+        assert_eq!(population.speed.min, ANT_SPEED);
+    }
+
+    #[test]
+    fn parse_population_errors() {
+        assert!("count".parse::<ColonyPopulation>().is_err());
+        assert!("count=many".parse::<ColonyPopulation>().is_err());
+        assert!("color=red".parse::<ColonyPopulation>().is_err());
+        assert!("archetype=queen".parse::<ColonyPopulation>().is_err());
+        assert!("region=room".parse::<ColonyPopulation>().is_err());
+        assert!("speed=fast".parse::<ColonyPopulation>().is_err());
+    }
+}
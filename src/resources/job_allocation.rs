@@ -0,0 +1,161 @@
+use bevy::prelude::*;
+use rand::{seq::SliceRandom, thread_rng, Rng};
+
+use crate::components::ants::{
+    caste::Caste, goal::Metrics, job::Job, live_ants::LiveAnt, movement::AntMovement,
+};
+
+/// Colony-level allocation of the jobs of live ants, using a response threshold model
+///
+/// Each task accumulates a stimulus from the needs of the colony (read from [Metrics]), which
+/// decreases with the share of ants already working on it. Ants idle on their task take a new one
+/// with a probability `s² / (s² + θ²)`, where `θ` is the threshold of their [Caste].
+#[derive(Debug, Clone, Resource, Reflect)]
+pub struct JobAllocation {
+    /// Seconds between two allocation rounds
+    pub period: f32,
+    /// Probability for an ant to reconsider its task during a round
+    pub reconsider_probability: f32,
+    /// Stored food under which foragers are needed
+    pub food_target: f32,
+    /// Threat at which the colony needs as many patrols as possible, see [Job::Patrol]
+    pub threat_target: f32,
+    /// Number of dead ants lying out of a midden at which undertakers are most needed
+    pub corpse_target: f32,
+    /// Decrease of a stimulus when all the ants work on the task
+    pub work_efficiency: f32,
+    pub tasks: Vec<TaskStimulus>,
+    #[reflect(ignore)]
+    last_round: f32,
+}
+
+impl Default for JobAllocation {
+    fn default() -> Self {
+        Self {
+            period: 1.,
+            reconsider_probability: 0.1,
+            food_target: 100.,
+            threat_target: 50.,
+            corpse_target: 20.,
+            work_efficiency: 2.,
+            tasks: [
+                Job::Food,
+                Job::Wander,
+                Job::Nurse,
+                Job::Patrol,
+                Job::Undertaker,
+            ]
+            .into_iter()
+            .map(TaskStimulus::new)
+            .collect(),
+            last_round: 0.,
+        }
+    }
+}
+
+impl JobAllocation {
+    /// Increase of the stimulus of a task during a round
+    pub fn demand(&self, task: Job, metrics: &Metrics) -> f32 {
+        match task {
+            Job::Food => (1. - metrics.food / self.food_target).max(0.),
+            Job::Patrol => (metrics.threat / self.threat_target).min(1.),
+            Job::Nurse => metrics.brood_misplaced as f32 / metrics.brood.max(1) as f32,
+            Job::Undertaker => (metrics.corpses as f32 / self.corpse_target).min(1.),
+            _ => 0.,
+        }
+    }
+
+    fn stimulus(&self, task: Job) -> Option<f32> {
+        self.tasks
+            .iter()
+            .find(|t| t.task == task)
+            .map(|t| t.stimulus)
+    }
+}
+
+/// State of a task, displayed in the debug panel
+#[derive(Debug, Clone, Copy, Reflect)]
+pub struct TaskStimulus {
+    pub task: Job,
+    pub stimulus: f32,
+    pub workers: usize,
+}
+
+impl TaskStimulus {
+    pub fn new(task: Job) -> Self {
+        Self {
+            task,
+            stimulus: 0.,
+            workers: 0,
+        }
+    }
+}
+
+fn response(stimulus: f32, threshold: f32) -> f32 {
+    let s2 = stimulus * stimulus;
+    s2 / (s2 + threshold * threshold)
+}
+
+pub fn allocate_jobs(
+    mut allocation: ResMut<JobAllocation>,
+    mut ants: Query<(&mut AntMovement, &Caste), With<LiveAnt>>,
+    metrics: Res<Metrics>,
+    time: Res<Time>,
+) {
+    let elapsed = time.elapsed_seconds();
+    if elapsed - allocation.last_round < allocation.period {
+        return;
+    }
+    allocation.last_round = elapsed;
+
+    // Update stimuli
+    let mut total = 0;
+    for task in allocation.tasks.iter_mut() {
+        task.workers = 0;
+    }
+    for (ant_movement, _) in ants.iter() {
+        total += 1;
+        let task = ant_movement.goal.job.task();
+        if let Some(task) = allocation.tasks.iter_mut().find(|t| t.task == task) {
+            task.workers += 1;
+        }
+    }
+    if total == 0 {
+        return;
+    }
+    for i in 0..allocation.tasks.len() {
+        let task = allocation.tasks[i];
+        let demand = allocation.demand(task.task, &metrics);
+        let work = allocation.work_efficiency * task.workers as f32 / total as f32;
+        allocation.tasks[i].stimulus = (task.stimulus + demand - work).max(0.);
+    }
+
+    // Reassign ants that are not in the middle of a round trip
+    let rng = &mut thread_rng();
+    for (mut ant_movement, caste) in ants.iter_mut() {
+        let goal = ant_movement.goal;
+        if goal.holds > 0. || goal.job.task() != goal.job {
+            continue;
+        }
+        if !rng.gen_bool(allocation.reconsider_probability.clamp(0., 1.) as f64) {
+            continue;
+        }
+        let Some(candidate) = allocation.tasks.choose(rng).map(|t| t.task) else {
+            continue;
+        };
+        if candidate == goal.job {
+            continue;
+        }
+        let current_response = allocation
+            .stimulus(goal.job)
+            .map(|s| response(s, caste.threshold(goal.job)))
+            .unwrap_or_default();
+        let candidate_response = allocation
+            .stimulus(candidate)
+            .map(|s| response(s, caste.threshold(candidate)))
+            .unwrap_or_default();
+        if rng.gen::<f32>() < candidate_response * (1. - current_response) {
+            ant_movement.goal.job = candidate;
+        }
+    }
+}
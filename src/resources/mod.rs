@@ -1,2 +1,4 @@
 pub mod clues;
+pub mod colony;
+pub mod job_allocation;
 pub mod nav_mesh_lut;
@@ -11,6 +11,8 @@ pub struct NavMeshLUT {
     pub grid_edges: Vec<TileEdges>,
     /// Identifies empty tiles
     pub grid_is_empty: Vec<bool>,
+    /// Identifies ground tiles, that nothing goes through
+    pub grid_is_ground: Vec<bool>,
     /// Number of tiles in X
     pub grid_width: usize,
     /// Number of tiles in Y
@@ -20,7 +22,17 @@ pub struct NavMeshLUT {
 }
 
 impl NavMeshLUT {
-    pub fn get_tile_entity(&self, mut pos: Vec2) -> Option<(Entity, usize)> {
+    pub fn get_tile_entity(&self, pos: Vec2) -> Option<(Entity, usize)> {
+        let index = self.get_tile_index(pos)?;
+        if !self.grid_is_empty[index] {
+            warn!("Trying to find a non-empty tile");
+            return None;
+        }
+        Some((self.grid_entity[index], index))
+    }
+
+    /// Index of the tile at `pos`, empty or not
+    pub fn get_tile_index(&self, mut pos: Vec2) -> Option<usize> {
         if pos.x < 0.
             || pos.y < 0.
             || pos.x > self.grid_width as f32 * TILE_SIZE
@@ -32,12 +44,7 @@ impl NavMeshLUT {
         pos.y = self.grid_height as f32 * TILE_SIZE - pos.y;
         let grid_pos_x = (pos.x / TILE_SIZE) as usize;
         let grid_pos_y = (pos.y / TILE_SIZE) as usize;
-        let index = grid_pos_x + grid_pos_y * self.grid_width;
-        if !self.grid_is_empty[index] {
-            warn!("Trying to find a non-empty tile");
-            return None;
-        }
-        Some((self.grid_entity[index], index))
+        Some(grid_pos_x + grid_pos_y * self.grid_width)
     }
 
     pub fn get_tile_entity_grid(&self, x: usize, y: usize) -> Option<(Entity, usize)> {
@@ -53,4 +60,24 @@ impl NavMeshLUT {
     pub fn get_tile_edges(&self, tile_index: usize) -> TileEdges {
         self.grid_edges[tile_index]
     }
+
+    /// Whether `node` is the empty tile at `pos` or one of its edges, from which an ant can reach
+    /// what lies on the tile
+    ///
+    /// Unlike [NavMeshLUT::get_tile_entity], it does not warn about objects pushed into the walls.
+    pub fn is_tile_node(&self, pos: Vec2, node: Entity) -> bool {
+        self.get_tile_index(pos)
+            .filter(|index| self.grid_is_empty[*index])
+            .is_some_and(|index| {
+                let edges = self.get_tile_edges(index);
+                [
+                    Some(self.grid_entity[index]),
+                    edges.up,
+                    edges.left,
+                    edges.down,
+                    edges.right,
+                ]
+                .contains(&Some(node))
+            })
+    }
 }
@@ -10,27 +10,26 @@ use bevy::{
 };
 
 use crate::{
-    components::ants::{zombants::ZombAntQueen, AntColorKind, AntStyle},
+    components::ants::{zombants::ZombAntQueen, AntColorKind, AntStyle, CarriedItem},
     render::render_ant::{AntMaterialBundle, ANT_MATERIAL_SIDE, ANT_MESH2D},
-    resources::clues::Clues,
+    resources::clues::{Clues, QueenClues},
     CLUE_COLOR, RENDERLAYER_CLUE_ANT,
 };
 
 pub struct UiCluesPlugin;
 impl Plugin for UiCluesPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, setup_ui_clues)
-            .add_systems(Update, update_ui_clues);
+        app.add_systems(Startup, setup_ui_clues).add_systems(
+            Update,
+            (update_queen_clues, update_ui_clues, update_ui_queens).chain(),
+        );
     }
 }
 
-pub fn setup_ui_clues(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
-    // Render an ant to an image
-    let ant_clue_image = images.add(new_ant_clue_image());
-    commands.spawn(new_ant_clue_camera(ant_clue_image.clone()));
-    let ant_clue = commands.spawn(new_ant_clue_bundle()).id();
+/// Distance between the ants rendered for the clues of each queen
+const ANT_CLUE_SPACING: f32 = 100.;
 
-    // UI
+pub fn setup_ui_clues(mut commands: Commands) {
     let root = commands
         .spawn((NodeBundle {
             style: Style {
@@ -46,6 +45,25 @@ pub fn setup_ui_clues(mut commands: Commands, mut images: ResMut<Assets<Image>>)
         },))
         .id();
 
+    let ui_queens_text = commands
+        .spawn(TextBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: 20.,
+                    color: CLUE_COLOR,
+                    ..default()
+                },
+            ),
+            style: Style {
+                margin: UiRect::all(Val::Px(5.)),
+                ..default()
+            },
+            ..default()
+        })
+        .set_parent(root)
+        .id();
+
     let ui_text = commands
         .spawn(TextBundle {
             text: Text::from_section(
@@ -77,22 +95,62 @@ pub fn setup_ui_clues(mut commands: Commands, mut images: ResMut<Assets<Image>>)
         .set_parent(root)
         .id();
 
-    let ui_ant_clue = commands
-        .spawn(new_clue_node(ant_clue_image))
-        .set_parent(ui_container)
-        .id();
-
     commands.insert_resource(Clues {
-        z0_primary_color: false,
-        z0_secondary_color: false,
+        queens: Vec::new(),
         pheromone_view_charges: 0,
         ui_text,
         ui_container,
-        ui_ant_clue,
-        ant_clue,
+        ui_queens_text,
     })
 }
 
+/// Start a clue chain for each new Zomb'Ant Queen, and drop the chains of the killed ones
+pub fn update_queen_clues(
+    mut commands: Commands,
+    mut clues: ResMut<Clues>,
+    mut images: ResMut<Assets<Image>>,
+    new_queens: Query<Entity, Added<ZombAntQueen>>,
+    mut killed_queens: RemovedComponents<ZombAntQueen>,
+    mut ant_clue_slots: Local<usize>,
+) {
+    for queen in killed_queens.read() {
+        let Some(index) = clues
+            .queens
+            .iter()
+            .position(|queen_clues| queen_clues.queen == queen)
+        else {
+            continue;
+        };
+        let queen_clues = clues.queens.remove(index);
+        let ui_container = clues.ui_container;
+        commands
+            .entity(ui_container)
+            .remove_children(&[queen_clues.ui_ant_clue]);
+        commands.entity(queen_clues.ui_ant_clue).despawn_recursive();
+        commands.entity(queen_clues.ant_clue).despawn_recursive();
+    }
+
+    for queen in new_queens.iter() {
+        // Render an ant to an image, each queen's ant away from the others
+        let offset = Vec3::X * ANT_CLUE_SPACING * *ant_clue_slots as f32;
+        *ant_clue_slots += 1;
+        let ant_clue_image = images.add(new_ant_clue_image());
+        let ant_clue = commands
+            .spawn(new_ant_clue_bundle(offset))
+            .with_children(|parent| {
+                parent.spawn(new_ant_clue_camera(ant_clue_image.clone()));
+            })
+            .id();
+        let ui_ant_clue = commands
+            .spawn(new_clue_node(ant_clue_image))
+            .set_parent(clues.ui_container)
+            .id();
+        clues
+            .queens
+            .push(QueenClues::new(queen, ui_ant_clue, ant_clue));
+    }
+}
+
 fn new_ant_clue_image() -> Image {
     let size = Extent3d {
         width: 100,
@@ -121,7 +179,8 @@ fn new_ant_clue_image() -> Image {
     image
 }
 
-fn new_ant_clue_bundle() -> impl Bundle {
+/// The ant parents the camera rendering it
+fn new_ant_clue_bundle(translation: Vec3) -> impl Bundle {
     (
         AntStyle {
             color_primary_kind: AntColorKind::WHITE,
@@ -129,11 +188,15 @@ fn new_ant_clue_bundle() -> impl Bundle {
             color_secondary_kind: AntColorKind::WHITE,
             color_secondary: Color::WHITE,
             animation_phase: 0.,
+            halo: 0.,
+            decay: 0.,
+            carried: CarriedItem::Nothing,
             scale: 1.,
         },
         AntMaterialBundle {
             mesh: ANT_MESH2D,
             material: ANT_MATERIAL_SIDE,
+            transform: Transform::from_translation(translation),
             ..default()
         },
         RENDERLAYER_CLUE_ANT,
@@ -198,13 +261,11 @@ pub fn update_ui_clues(
     mut ui_nodes: Query<&mut Visibility, With<Node>>,
     clue_nodes: Query<Entity, With<ClueNode>>,
     mut ant_styles: Query<&mut AntStyle>,
-    zombant_queens: Query<Entity, (With<ZombAntQueen>, With<AntStyle>)>,
 ) {
     if !clues.is_changed() {
         return;
     }
-    let show_ant_clue = clues.z0_primary_color || clues.z0_secondary_color;
-    let show_text = show_ant_clue || (clues.pheromone_view_charges > 0);
+    let show_text = clues.any_revealed() || (clues.pheromone_view_charges > 0);
 
     let Ok(mut text_visibility) = ui_nodes.get_mut(clues.ui_text) else {
         return;
@@ -215,31 +276,41 @@ pub fn update_ui_clues(
         Visibility::Hidden
     });
 
-    let Ok(mut ant_clue_visibility) = ui_nodes.get_mut(clues.ui_ant_clue) else {
-        return;
-    };
-    ant_clue_visibility.set_if_neq(if show_ant_clue {
-        Visibility::Inherited
-    } else {
-        Visibility::Hidden
-    });
-    if let Ok(zombant_queen) = zombant_queens.get_single() {
-        let zombant_queen_style = *ant_styles.get(zombant_queen).unwrap();
-        let mut ant_clue_style = ant_styles.get_mut(clues.ant_clue).unwrap();
-        if clues.z0_primary_color {
+    for queen_clues in clues.queens.iter() {
+        let Ok(mut ant_clue_visibility) = ui_nodes.get_mut(queen_clues.ui_ant_clue) else {
+            continue;
+        };
+        ant_clue_visibility.set_if_neq(if queen_clues.revealed() > 0 {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+        let Ok(zombant_queen_style) = ant_styles.get(queen_clues.queen).copied() else {
+            continue;
+        };
+        let Ok(mut ant_clue_style) = ant_styles.get_mut(queen_clues.ant_clue) else {
+            continue;
+        };
+        if queen_clues.z0_primary_color {
             ant_clue_style.color_primary_kind = zombant_queen_style.color_primary_kind;
             ant_clue_style.color_primary = zombant_queen_style.color_primary;
         }
-        if clues.z0_secondary_color {
+        if queen_clues.z0_secondary_color {
             ant_clue_style.color_secondary_kind = zombant_queen_style.color_secondary_kind;
             ant_clue_style.color_secondary = zombant_queen_style.color_secondary;
         }
     }
 
-    let existing_clue_nodes = clue_nodes.iter().len();
-    if (existing_clue_nodes - 1) != clues.pheromone_view_charges {
+    let is_ant_clue = |entity: Entity| {
+        clues
+            .queens
+            .iter()
+            .any(|queen_clues| queen_clues.ui_ant_clue == entity)
+    };
+    let existing_clue_nodes = clue_nodes.iter().filter(|e| !is_ant_clue(*e)).count();
+    if existing_clue_nodes != clues.pheromone_view_charges {
         for entity in clue_nodes.iter() {
-            if entity == clues.ui_ant_clue {
+            if is_ant_clue(entity) {
                 continue;
             }
             commands
@@ -254,3 +325,22 @@ pub fn update_ui_clues(
         }
     }
 }
+
+/// Show how many Zomb'Ant Queens are left to kill
+pub fn update_ui_queens(
+    clues: Res<Clues>,
+    queens: Query<(), With<ZombAntQueen>>,
+    mut texts: Query<&mut Text>,
+) {
+    let Ok(mut text) = texts.get_mut(clues.ui_queens_text) else {
+        return;
+    };
+    let remaining = match queens.iter().count() {
+        0 => String::new(),
+        1 => "1 Zomb'Ant Queen left".to_string(),
+        n => format!("{n} Zomb'Ant Queens left"),
+    };
+    if text.sections[0].value != remaining {
+        text.sections[0].value = remaining;
+    }
+}
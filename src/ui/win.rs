@@ -1,5 +1,7 @@
 use bevy::prelude::*;
 
+use crate::GameOverCause;
+
 #[derive(Component)]
 pub struct WinText;
 
@@ -9,35 +11,63 @@ pub fn display_win(
     win: Query<&WinText>,
 ) {
     if win.get_single().is_err() {
-        for entity in entities.iter() {
-            commands.entity(entity).despawn();
-        }
+        display_end_text(
+            &mut commands,
+            &entities,
+            "You killed every Zomb'Ant Queen!\nYou win!",
+        );
+    }
+}
 
-        commands.spawn(Camera2dBundle::default());
-        let root = commands
-            .spawn(NodeBundle {
-                style: Style {
-                    justify_content: JustifyContent::Center,
-                    align_items: AlignItems::Center,
-                    width: Val::Percent(100.),
-                    height: Val::Percent(100.),
-                    ..default()
-                },
-                ..default()
-            })
-            .id();
-        commands
-            .spawn((
-                TextBundle::from_section(
-                    "You killed the Zomb'Ant Queen!\nYou win!",
-                    TextStyle {
-                        font_size: 120.0,
-                        ..Default::default()
-                    },
-                )
-                .with_text_alignment(TextAlignment::Center),
-                WinText,
-            ))
-            .set_parent(root);
+pub fn display_game_over(
+    mut commands: Commands,
+    entities: Query<Entity, Without<Window>>,
+    win: Query<&WinText>,
+    game_over_cause: Res<GameOverCause>,
+) {
+    if win.get_single().is_err() {
+        let text = match *game_over_cause {
+            GameOverCause::ColonyQueenKilled => "You killed the colony's Queen!\nGame over",
+            GameOverCause::PlayerKilled => "The soldiers killed you!\nGame over",
+        };
+        display_end_text(&mut commands, &entities, text);
+    }
+}
+
+/// Clear the screen and display `text` in the middle of it
+fn display_end_text(
+    commands: &mut Commands,
+    entities: &Query<Entity, Without<Window>>,
+    text: &str,
+) {
+    for entity in entities.iter() {
+        commands.entity(entity).despawn();
     }
+
+    commands.spawn(Camera2dBundle::default());
+    let root = commands
+        .spawn(NodeBundle {
+            style: Style {
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                ..default()
+            },
+            ..default()
+        })
+        .id();
+    commands
+        .spawn((
+            TextBundle::from_section(
+                text,
+                TextStyle {
+                    font_size: 120.0,
+                    ..Default::default()
+                },
+            )
+            .with_text_alignment(TextAlignment::Center),
+            WinText,
+        ))
+        .set_parent(root);
 }